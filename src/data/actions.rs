@@ -1,8 +1,16 @@
 use super::equipment::StringOrNum;
 use super::traits::Traits;
-use crate::data::{action_type::ActionType, traits::JsonTraits, ValueWrapper};
+use crate::data::{action_type::ActionType, outcomes::Outcomes, traits::JsonTraits, ValueWrapper};
 use crate::text_cleanup;
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+lazy_static! {
+    static ref BLOCK_PARAGRAPH_REGEX: Regex =
+        Regex::new(r"<p>\s*<strong>(Frequency|Trigger|Requirements?|Effect)</strong>\s*(.*?)\s*</p>").unwrap();
+}
 
 #[derive(Deserialize)]
 pub struct JsonAction {
@@ -28,13 +36,85 @@ pub struct Action {
     pub action_type: ActionType,
     pub number_of_actions: Option<i32>,
     pub traits: Traits,
+    pub block: ActionBlock,
+    pub outcomes: Outcomes,
+}
+
+/// The labelled parts of an activity that Foundry only has as bold paragraphs in the description.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Eq, Default)]
+pub struct ActionBlock {
+    pub frequency: Option<String>,
+    pub trigger: Option<String>,
+    pub requirements: Option<String>,
+    pub effect: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, AsRefStr)]
+pub enum BlockLabel {
+    Frequency,
+    Trigger,
+    Requirements,
+    Effect,
+}
+
+impl ActionBlock {
+    pub fn parse(description: &str) -> Self {
+        let mut block = ActionBlock::default();
+        for (label, _, text) in block_paragraphs(description) {
+            let field = match label {
+                BlockLabel::Frequency => &mut block.frequency,
+                BlockLabel::Trigger => &mut block.trigger,
+                BlockLabel::Requirements => &mut block.requirements,
+                BlockLabel::Effect => &mut block.effect,
+            };
+            *field = Some(text.to_string());
+        }
+        block
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &ActionBlock::default()
+    }
+
+    /// Everything that is shown above the line, i.e. all but the effect.
+    pub fn header(&self) -> impl Iterator<Item = (BlockLabel, &str)> {
+        [
+            (BlockLabel::Frequency, &self.frequency),
+            (BlockLabel::Trigger, &self.trigger),
+            (BlockLabel::Requirements, &self.requirements),
+        ]
+        .into_iter()
+        .filter_map(|(label, text)| Some((label, text.as_deref()?)))
+    }
+}
+
+/// The first paragraph for each label in the order they appear in the description.
+/// Only the first one counts because later ones usually belong to a different activation.
+pub fn block_paragraphs(description: &str) -> Vec<(BlockLabel, Range<usize>, &str)> {
+    let mut paragraphs: Vec<(BlockLabel, Range<usize>, &str)> = Vec::new();
+    for captures in BLOCK_PARAGRAPH_REGEX.captures_iter(description) {
+        let label = match &captures[1] {
+            "Frequency" => BlockLabel::Frequency,
+            "Trigger" => BlockLabel::Trigger,
+            "Effect" => BlockLabel::Effect,
+            _ => BlockLabel::Requirements,
+        };
+        if paragraphs.iter().all(|(l, _, _)| *l != label) {
+            let text = captures.get(2).map(|m| m.as_str()).unwrap_or_default();
+            paragraphs.push((label, captures.get(0).unwrap().range(), text));
+        }
+    }
+    paragraphs
 }
 
 impl From<JsonAction> for Action {
     fn from(ja: JsonAction) -> Self {
+        let description = text_cleanup(&ja.system.description.value);
         Action {
             name: ja.name.clone(),
-            description: text_cleanup(&ja.system.description.value),
+            block: ActionBlock::parse(&description),
+            outcomes: Outcomes::parse(&description),
+            description,
             action_type: ja.system.action_type.value,
             number_of_actions: ja.system.number_of_actions.value.map(i32::from).filter(|&n| n != 0),
             traits: Traits::from(ja.system.traits),
@@ -62,5 +142,25 @@ mod test {
                 alignment: None,
             }
         );
+        assert_eq!(
+            aid.block.trigger.as_deref(),
+            Some("An ally is about to use an action that requires a skill check or attack roll")
+        );
+        assert!(aid.block.requirements.is_some());
+    }
+
+    #[test]
+    fn should_parse_action_block() {
+        let description = "<p><strong>Frequency</strong> once per day</p>\n<p><strong>Requirement</strong> You are wielding a shield</p>\n<hr />\n<p>You brace.</p>\n<p><strong>Effect</strong> You gain <em>resistance</em>.</p>\n<p><strong>Effect</strong> Another activation</p>";
+        assert_eq!(
+            ActionBlock::parse(description),
+            ActionBlock {
+                frequency: Some("once per day".to_string()),
+                trigger: None,
+                requirements: Some("You are wielding a shield".to_string()),
+                effect: Some("You gain <em>resistance</em>.".to_string()),
+            }
+        );
+        assert!(ActionBlock::parse("<p>You brace.</p>").is_empty());
     }
 }
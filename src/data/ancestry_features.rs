@@ -1,4 +1,5 @@
 use crate::data::feat_type::FeatType;
+use crate::data::rules::Rule;
 use crate::data::traits::{JsonTraits, Traits};
 use crate::data::ValueWrapper;
 use crate::text_cleanup;
@@ -16,6 +17,8 @@ pub struct AncestryFeatureData {
     description: ValueWrapper<String>,
     feat_type: ValueWrapper<FeatType>,
     traits: JsonTraits,
+    #[serde(default)]
+    rules: Vec<Rule>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -25,6 +28,8 @@ pub struct AncestryFeature {
     pub description: String,
     pub feat_type: FeatType,
     pub traits: Traits,
+    #[serde(skip_serializing)]
+    pub rules: Vec<Rule>,
 }
 
 impl From<JsonAncestryFeature> for AncestryFeature {
@@ -34,6 +39,7 @@ impl From<JsonAncestryFeature> for AncestryFeature {
             description: text_cleanup(&jaf.system.description.value),
             feat_type: jaf.system.feat_type.value,
             traits: Traits::from(jaf.system.traits),
+            rules: jaf.system.rules,
         }
     }
 }
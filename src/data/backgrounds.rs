@@ -1,5 +1,5 @@
 use super::{
-    ability_scores::{AbilityBoost, JsonAbilityBoosts},
+    ability_scores::{AbilityBoost, AbilityScore, JsonAbilityBoosts},
     skills::Skill,
     traits::{JsonTraits, Traits},
     HasName, ValueWrapper,
@@ -24,6 +24,11 @@ pub struct Background {
 }
 
 impl Background {
+    /// Whether one of the fixed (i.e. not free) boosts can be put into this ability score.
+    pub fn boosts_ability(&self, ability: AbilityScore) -> bool {
+        self.boosts.iter().any(|b| !b.is_free() && b.0.contains(&ability))
+    }
+
     pub fn condensed(&self) -> String {
         let feats = self
             .feats
@@ -87,10 +92,7 @@ struct JsonFeatReference {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        data::{ability_scores::AbilityScore, traits::Rarity},
-        tests::read_test_file,
-    };
+    use crate::{data::traits::Rarity, tests::read_test_file};
 
     #[test]
     fn test_field_medic_deserialization() {
@@ -1,5 +1,7 @@
 use crate::data::action_type::ActionType;
+use crate::data::class_progression::ProficiencyChange;
 use crate::data::feat_type::FeatType;
+use crate::data::rules::Rule;
 use crate::data::traits::{JsonTraits, Traits};
 use crate::data::ValueWrapper;
 use crate::text_cleanup;
@@ -27,6 +29,8 @@ pub struct ClassFeatureData {
     level: ValueWrapper<i32>,
     feat_type: ValueWrapper<FeatType>,
     traits: JsonTraits,
+    #[serde(default)]
+    rules: Vec<Rule>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -39,6 +43,14 @@ pub struct ClassFeature {
     pub number_of_actions: Option<i32>,
     pub level: i32,
     pub traits: Traits,
+    #[serde(skip_serializing)]
+    pub rules: Vec<Rule>,
+}
+
+impl ClassFeature {
+    pub fn proficiency_increases(&self) -> impl Iterator<Item = ProficiencyChange> + '_ {
+        self.rules.iter().filter_map(Rule::proficiency_change)
+    }
 }
 
 impl From<JsonClassFeature> for ClassFeature {
@@ -51,6 +63,7 @@ impl From<JsonClassFeature> for ClassFeature {
             level: jcf.system.level.value,
             number_of_actions: jcf.system.number_of_actions.value,
             traits: Traits::from(jcf.system.traits),
+            rules: jcf.system.rules,
         }
     }
 }
@@ -58,7 +71,10 @@ impl From<JsonClassFeature> for ClassFeature {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{data::traits::Rarity, tests::read_test_file};
+    use crate::{
+        data::{class_progression::Statistic, proficiency::Proficiency, traits::Rarity},
+        tests::read_test_file,
+    };
 
     #[test]
     fn should_deserialize_real_class_feature() {
@@ -78,4 +94,39 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn should_parse_proficiency_rules() {
+        let json = r#"{
+            "name": "Juggernaut",
+            "system": {
+                "actionType": { "value": "passive" },
+                "actions": { "value": null },
+                "description": { "value": "<p>Your Fortitude save becomes master.</p>" },
+                "level": { "value": 7 },
+                "featType": { "value": "classfeature" },
+                "traits": { "value": ["fighter"], "rarity": "common" },
+                "rules": [
+                    { "key": "ActiveEffectLike", "mode": "upgrade", "path": "system.saves.fortitude.rank", "value": 3 },
+                    { "key": "ActiveEffectLike", "mode": "upgrade", "path": "system.martial.simple.rank", "value": "2" },
+                    { "key": "ActiveEffectLike", "mode": "add", "path": "system.attributes.speed.value", "value": 5 },
+                    { "key": "FlatModifier", "selector": "ac", "value": 1 }
+                ]
+            }
+        }"#;
+        let juggernaut: ClassFeature = serde_json::from_str(json).expect("Deserialization failed");
+        assert_eq!(
+            juggernaut.proficiency_increases().collect::<Vec<_>>(),
+            vec![
+                ProficiencyChange {
+                    statistic: Statistic::Fortitude,
+                    rank: Proficiency::Master,
+                },
+                ProficiencyChange {
+                    statistic: Statistic::Simple,
+                    rank: Proficiency::Expert,
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,368 @@
+use super::{
+    class_features::ClassFeature,
+    classes::{Class, ClassItem},
+    proficiency::Proficiency,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+pub const MAX_LEVEL: i32 = 20;
+pub const ABILITY_BOOST_LEVELS: &[i32] = &[5, 10, 15, 20];
+
+/// Everything a character of the given class gains on each level from 1 to 20.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct ClassProgression {
+    pub class: String,
+    pub levels: Vec<LevelProgression>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct LevelProgression {
+    pub level: i32,
+    pub features: Vec<String>,
+    pub feat_slots: Vec<FeatSlot>,
+    pub ability_boosts: bool,
+    pub skill_increase: bool,
+    pub proficiencies: Vec<ProficiencyChange>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy, AsRefStr)]
+pub enum FeatSlot {
+    Ancestry,
+    Class,
+    General,
+    Skill,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
+pub enum Statistic {
+    Perception,
+    Fortitude,
+    Reflex,
+    Will,
+    Unarmed,
+    Simple,
+    Martial,
+    Advanced,
+    OtherWeapons(String),
+    Unarmored,
+    LightArmor,
+    MediumArmor,
+    HeavyArmor,
+    ClassDc,
+}
+
+impl Statistic {
+    /// Maps the target of a Foundry `ActiveEffectLike` rule to the statistic it upgrades.
+    pub fn from_rule_path(path: &str) -> Option<Self> {
+        let path = path.strip_prefix("system.")?.strip_suffix(".rank")?;
+        match path.rsplit_once('.')?.1 {
+            "perception" => Some(Statistic::Perception),
+            "fortitude" => Some(Statistic::Fortitude),
+            "reflex" => Some(Statistic::Reflex),
+            "will" => Some(Statistic::Will),
+            "unarmed" => Some(Statistic::Unarmed),
+            "simple" => Some(Statistic::Simple),
+            "martial" => Some(Statistic::Martial),
+            "advanced" => Some(Statistic::Advanced),
+            "unarmored" => Some(Statistic::Unarmored),
+            "light" => Some(Statistic::LightArmor),
+            "medium" => Some(Statistic::MediumArmor),
+            "heavy" => Some(Statistic::HeavyArmor),
+            "classDC" => Some(Statistic::ClassDc),
+            // Newer data has one DC per class, e.g. `system.proficiencies.classDCs.fighter.rank`
+            _ if path.contains("classDCs.") => Some(Statistic::ClassDc),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Statistic::Perception => "Perception",
+            Statistic::Fortitude => "Fortitude",
+            Statistic::Reflex => "Reflex",
+            Statistic::Will => "Will",
+            Statistic::Unarmed => "Unarmed Attacks",
+            Statistic::Simple => "Simple Weapons",
+            Statistic::Martial => "Martial Weapons",
+            Statistic::Advanced => "Advanced Weapons",
+            Statistic::OtherWeapons(name) => name,
+            Statistic::Unarmored => "Unarmored Defense",
+            Statistic::LightArmor => "Light Armor",
+            Statistic::MediumArmor => "Medium Armor",
+            Statistic::HeavyArmor => "Heavy Armor",
+            Statistic::ClassDc => "Class DC",
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
+pub struct ProficiencyChange {
+    pub statistic: Statistic,
+    pub rank: Proficiency,
+}
+
+impl ClassProgression {
+    pub fn new<'a>(class: &Class, all_features: impl IntoIterator<Item = &'a ClassFeature>) -> Self {
+        // Several classes have features with the same name, like Weapon Expertise, so only this class’s count.
+        let class_trait = class.name.to_lowercase();
+        let features_by_name: HashMap<_, _> = all_features
+            .into_iter()
+            .filter(|f| f.traits.misc.contains(&class_trait))
+            .map(|f| (f.name.as_str(), f))
+            .collect();
+        let initial = initial_proficiencies(class);
+        let levels = (1..=MAX_LEVEL)
+            .map(|level| {
+                let features = features_at_level(&class.class_features, level);
+                let mut proficiencies = if level == 1 { initial.clone() } else { Vec::new() };
+                proficiencies.extend(
+                    features
+                        .iter()
+                        .filter_map(|f| features_by_name.get(f.as_str()))
+                        .flat_map(|f| f.proficiency_increases()),
+                );
+                // Keep only the highest rank if several features touch the same statistic.
+                proficiencies.sort_by(|a, b| a.statistic.cmp(&b.statistic).then(b.rank.cmp(&a.rank)));
+                proficiencies.dedup_by(|a, b| a.statistic == b.statistic);
+                (level, features, proficiencies)
+            })
+            .map(|(level, features, proficiencies)| LevelProgression {
+                level,
+                features,
+                feat_slots: [
+                    (FeatSlot::Ancestry, &class.ancestry_feat_levels),
+                    (FeatSlot::Class, &class.class_feat_levels),
+                    (FeatSlot::General, &class.general_feat_levels),
+                    (FeatSlot::Skill, &class.skill_feat_levels),
+                ]
+                .into_iter()
+                .filter(|(_, levels)| levels.contains(&level))
+                .map(|(slot, _)| slot)
+                .collect(),
+                ability_boosts: ABILITY_BOOST_LEVELS.contains(&level),
+                skill_increase: class.skill_increase_levels.contains(&level),
+                proficiencies,
+            })
+            .collect();
+        ClassProgression {
+            class: class.name.clone(),
+            levels,
+        }
+    }
+
+    /// The rank in each statistic at every level, for all statistics the class is ever trained in.
+    pub fn proficiency_table(&self) -> BTreeMap<Statistic, Vec<Proficiency>> {
+        let mut table: BTreeMap<Statistic, Vec<Proficiency>> = BTreeMap::new();
+        for (index, level) in self.levels.iter().enumerate() {
+            for change in &level.proficiencies {
+                let ranks = table
+                    .entry(change.statistic.clone())
+                    .or_insert_with(|| vec![Proficiency::Untrained; self.levels.len()]);
+                for rank in &mut ranks[index..] {
+                    *rank = (*rank).max(change.rank);
+                }
+            }
+        }
+        table
+    }
+}
+
+fn features_at_level(features: &[ClassItem], level: i32) -> Vec<String> {
+    let mut names: Vec<_> = features
+        .iter()
+        .filter(|f| f.level == level)
+        .map(|f| f.name.trim_start_matches("(Choice) ").to_owned())
+        .collect();
+    // The class JSON stores features in a map, so the order would otherwise be random.
+    names.sort();
+    names
+}
+
+fn initial_proficiencies(class: &Class) -> Vec<ProficiencyChange> {
+    let mut initial = vec![
+        (Statistic::Perception, class.perception),
+        (Statistic::Fortitude, class.saving_throws.fortitude),
+        (Statistic::Reflex, class.saving_throws.reflex),
+        (Statistic::Will, class.saving_throws.will),
+        (Statistic::Unarmed, class.attacks.unarmed),
+        (Statistic::Simple, class.attacks.simple),
+        (Statistic::Martial, class.attacks.martial),
+        (Statistic::Advanced, class.attacks.advanced),
+        (Statistic::Unarmored, class.defenses.unarmored),
+        (Statistic::LightArmor, class.defenses.light),
+        (Statistic::MediumArmor, class.defenses.medium),
+        (Statistic::HeavyArmor, class.defenses.heavy),
+        (Statistic::ClassDc, class.class_dc),
+    ];
+    if !class.attacks.other.name.is_empty() {
+        initial.push((Statistic::OtherWeapons(class.attacks.other.name.clone()), class.attacks.other.rank));
+    }
+    initial.sort();
+    initial
+        .into_iter()
+        .filter(|(_, rank)| rank != &Proficiency::Untrained)
+        .map(|(statistic, rank)| ProficiencyChange { statistic, rank })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{
+        action_type::ActionType,
+        classes::{AttackProficiencies, DefensiveProficiencies, OtherAttacksProficiencies, SavingThrowProficiencies},
+        feat_type::FeatType,
+        rules::Rule,
+        traits::{Rarity, Traits},
+    };
+
+    fn test_class() -> Class {
+        Class {
+            name: String::from("Tester"),
+            ancestry_feat_levels: vec![1, 5, 9, 13, 17],
+            attacks: AttackProficiencies {
+                unarmed: Proficiency::Trained,
+                simple: Proficiency::Trained,
+                martial: Proficiency::Untrained,
+                advanced: Proficiency::Untrained,
+                other: OtherAttacksProficiencies {
+                    name: String::new(),
+                    rank: Proficiency::Untrained,
+                },
+            },
+            class_dc: Proficiency::Trained,
+            class_feat_levels: vec![2, 4, 6, 8, 10, 12, 14, 16, 18, 20],
+            defenses: DefensiveProficiencies {
+                unarmored: Proficiency::Trained,
+                light: Proficiency::Untrained,
+                medium: Proficiency::Untrained,
+                heavy: Proficiency::Untrained,
+            },
+            description: String::new(),
+            general_feat_levels: vec![3, 7, 11, 15, 19],
+            hp: 6,
+            key_ability: vec![],
+            perception: Proficiency::Trained,
+            saving_throws: SavingThrowProficiencies {
+                fortitude: Proficiency::Trained,
+                reflex: Proficiency::Trained,
+                will: Proficiency::Expert,
+            },
+            skill_feat_levels: vec![2, 4, 6, 8, 10, 12, 14, 16, 18, 20],
+            skill_increase_levels: vec![3, 5, 7, 9, 11, 13, 15, 17, 19],
+            trained_skills: vec![],
+            free_skills: 2,
+            traits: Traits {
+                misc: vec![],
+                rarity: Rarity::Common,
+                alignment: None,
+                size: None,
+            },
+            class_features: vec![
+                ClassItem {
+                    name: String::from("Spellcasting"),
+                    level: 1,
+                },
+                ClassItem {
+                    name: String::from("(Choice) Arcane School"),
+                    level: 1,
+                },
+                ClassItem {
+                    name: String::from("Lightning Reflexes"),
+                    level: 5,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn progression_test() {
+        let progression = ClassProgression::new(&test_class(), &[]);
+        assert_eq!(progression.levels.len(), 20);
+        let first = &progression.levels[0];
+        assert_eq!(first.features, vec!["Arcane School", "Spellcasting"]);
+        assert_eq!(first.feat_slots, vec![FeatSlot::Ancestry]);
+        assert!(!first.ability_boosts);
+        assert_eq!(
+            first.proficiencies.iter().map(|p| p.statistic.label()).collect::<Vec<_>>(),
+            vec![
+                "Perception",
+                "Fortitude",
+                "Reflex",
+                "Will",
+                "Unarmed Attacks",
+                "Simple Weapons",
+                "Unarmored Defense",
+                "Class DC"
+            ]
+        );
+        let fifth = &progression.levels[4];
+        assert_eq!(fifth.features, vec!["Lightning Reflexes"]);
+        assert_eq!(fifth.feat_slots, vec![FeatSlot::Ancestry]);
+        assert!(fifth.ability_boosts);
+        assert!(fifth.skill_increase);
+        assert!(fifth.proficiencies.is_empty());
+        assert_eq!(progression.levels[1].feat_slots, vec![FeatSlot::Class, FeatSlot::Skill]);
+    }
+
+    #[test]
+    fn proficiency_table_test() {
+        let lightning_reflexes = |class: &str, rank: i32| ClassFeature {
+            name: String::from("Lightning Reflexes"),
+            description: String::new(),
+            feat_type: FeatType::ClassFeature,
+            action_type: ActionType::Passive,
+            number_of_actions: None,
+            level: 5,
+            traits: Traits {
+                misc: vec![class.to_string()],
+                rarity: Rarity::Common,
+                alignment: None,
+                size: None,
+            },
+            rules: vec![Rule::ActiveEffectLike {
+                mode: String::from("upgrade"),
+                path: String::from("system.saves.reflex.rank"),
+                value: rank.into(),
+            }],
+        };
+        // the other class’s feature with the same name must not be charted for this class
+        let features = [lightning_reflexes("tester", 2), lightning_reflexes("rogue", 3)];
+        let progression = ClassProgression::new(&test_class(), &features);
+        assert_eq!(
+            progression.levels[4].proficiencies,
+            vec![ProficiencyChange {
+                statistic: Statistic::Reflex,
+                rank: Proficiency::Expert,
+            }]
+        );
+        let table = progression.proficiency_table();
+        assert_eq!(table[&Statistic::Reflex][3], Proficiency::Trained);
+        assert_eq!(table[&Statistic::Reflex][4], Proficiency::Expert);
+        assert_eq!(table[&Statistic::Reflex][19], Proficiency::Expert);
+        assert_eq!(table[&Statistic::Will][0], Proficiency::Expert);
+        assert!(!table.contains_key(&Statistic::HeavyArmor));
+    }
+
+    #[test]
+    fn rule_path_test() {
+        assert_eq!(Statistic::from_rule_path("system.saves.reflex.rank"), Some(Statistic::Reflex));
+        assert_eq!(
+            Statistic::from_rule_path("system.attributes.perception.rank"),
+            Some(Statistic::Perception)
+        );
+        assert_eq!(Statistic::from_rule_path("system.martial.martial.rank"), Some(Statistic::Martial));
+        assert_eq!(Statistic::from_rule_path("system.martial.heavy.rank"), Some(Statistic::HeavyArmor));
+        assert_eq!(
+            Statistic::from_rule_path("system.proficiencies.defenses.unarmored.rank"),
+            Some(Statistic::Unarmored)
+        );
+        assert_eq!(
+            Statistic::from_rule_path("system.attributes.classDC.rank"),
+            Some(Statistic::ClassDc)
+        );
+        assert_eq!(Statistic::from_rule_path("system.skills.ath.rank"), None);
+        assert_eq!(Statistic::from_rule_path("system.attributes.speed.value"), None);
+    }
+}
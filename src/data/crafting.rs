@@ -0,0 +1,145 @@
+use super::{
+    equipment::{Equipment, ItemType, Price},
+    proficiency::Proficiency,
+    traits::Rarity,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref CRAFT_REQUIREMENTS_REGEX: Regex =
+        Regex::new(r"(?:<hr ?/>\s*)?<p>\s*<strong>Craft Requirements?</strong>:?\s*(.*?)\s*</p>\s*").unwrap();
+}
+
+/// Removes the craft requirements paragraph from an item description and returns its text separately.
+pub fn split_craft_requirements(description: &str) -> (String, Option<String>) {
+    match CRAFT_REQUIREMENTS_REGEX.captures(description) {
+        Some(captures) => {
+            let paragraph = captures.get(0).unwrap().range();
+            let requirements = captures[1].to_string();
+            let mut rest = description[..paragraph.start].to_string();
+            rest.push_str(&description[paragraph.end..]);
+            (rest.trim_end().to_string(), Some(requirements))
+        }
+        None => (description.to_string(), None),
+    }
+}
+
+/// Formula price by item level (Core Rulebook p. 293)
+pub fn formula_price(level: i32) -> Option<Price> {
+    let copper = match level {
+        0 => 50,
+        1 => 100,
+        2 => 200,
+        3 => 300,
+        4 => 500,
+        5 => 800,
+        6 => 1_300,
+        7 => 1_800,
+        8 => 2_500,
+        9 => 3_500,
+        10 => 5_000,
+        11 => 7_000,
+        12 => 10_000,
+        13 => 15_000,
+        14 => 22_500,
+        15 => 32_500,
+        16 => 50_000,
+        17 => 75_000,
+        18 => 120_000,
+        19 => 200_000,
+        20 => 350_000,
+        _ => return None,
+    };
+    Some(Price::from_copper(copper))
+}
+
+/// DCs by level (Core Rulebook p. 503) adjusted for rarity
+pub fn crafting_dc(level: i32, rarity: Rarity) -> Option<i32> {
+    const DC_BY_LEVEL: [i32; 26] = [
+        14, 15, 16, 18, 19, 20, 22, 23, 24, 26, 27, 28, 30, 31, 32, 34, 35, 36, 38, 39, 40, 42, 44, 46, 48, 50,
+    ];
+    let adjustment = match rarity {
+        Rarity::Common => 0,
+        Rarity::Uncommon => 2,
+        Rarity::Rare => 5,
+        Rarity::Unique => 10,
+    };
+    usize::try_from(level)
+        .ok()
+        .and_then(|l| DC_BY_LEVEL.get(l))
+        .map(|dc| dc + adjustment)
+}
+
+/// What it takes to Craft an item, `None` for treasure, artifacts, unique items and items beyond level 20.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CraftingInfo<'a> {
+    pub formula_price: Price,
+    pub dc: i32,
+    pub proficiency: Proficiency,
+    pub feats: Vec<&'static str>,
+    pub requirements: Option<&'a str>,
+}
+
+impl<'a> CraftingInfo<'a> {
+    pub fn new(item: &'a Equipment) -> Option<Self> {
+        let has_trait = |t: &str| item.traits.misc.iter().any(|misc| misc == t);
+        // Coins, gems and art objects aren’t crafted
+        if item.item_type == ItemType::Treasure || has_trait("artifact") || item.traits.rarity == Rarity::Unique {
+            return None;
+        }
+        let proficiency = match item.level {
+            16.. => Proficiency::Legendary,
+            9.. => Proficiency::Master,
+            _ => Proficiency::Trained,
+        };
+        let magical = ["magical", "arcane", "divine", "occult", "primal"].iter().any(|t| has_trait(t));
+        let feats = [
+            (has_trait("alchemical"), "Alchemical Crafting"),
+            (magical, "Magical Crafting"),
+            (has_trait("snare"), "Snare Crafting"),
+        ]
+        .into_iter()
+        .filter_map(|(needed, feat)| needed.then_some(feat))
+        .collect();
+        Some(CraftingInfo {
+            formula_price: formula_price(item.level)?,
+            dc: crafting_dc(item.level, item.traits.rarity)?,
+            proficiency,
+            feats,
+            requirements: item.craft_requirements.as_deref(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_split_craft_requirements() {
+        let description = "<p>A bomb.</p>\n<hr />\n<p><strong>Craft Requirements</strong> Supply a vial of blood.</p>";
+        assert_eq!(
+            split_craft_requirements(description),
+            (String::from("<p>A bomb.</p>"), Some(String::from("Supply a vial of blood.")))
+        );
+        assert_eq!(split_craft_requirements("<p>A rope.</p>"), (String::from("<p>A rope.</p>"), None));
+    }
+
+    #[test]
+    fn should_look_up_formulas_and_dcs() {
+        assert_eq!(formula_price(0), Some(Price::from_copper(50)));
+        assert_eq!(formula_price(17).map(|p| p.to_string()), Some(String::from("750 gp ")));
+        assert_eq!(formula_price(21), None);
+        assert_eq!(crafting_dc(3, Rarity::Common), Some(18));
+        assert_eq!(crafting_dc(20, Rarity::Uncommon), Some(42));
+        assert_eq!(crafting_dc(-1, Rarity::Common), None);
+    }
+
+    #[test]
+    fn should_not_craft_treasure() {
+        let item = |name: &str, item_type: &str| crate::tests::equipment(name, item_type, 0, 0, "common", &[], serde_json::json!({}));
+        assert_eq!(CraftingInfo::new(&item("Gold Pieces", "treasure")), None);
+        assert!(CraftingInfo::new(&item("Rope", "equipment")).is_some());
+    }
+}
@@ -86,6 +86,100 @@ pub struct Creature {
     pub actions: Vec<Action>,
 }
 
+/// Name prefixes for the age or strength of a creature, e.g. `Young Red Dragon` or `Greater Barghest`.
+const VARIANT_PREFIXES: [&str; 10] = [
+    "Young ",
+    "Adult ",
+    "Ancient ",
+    "Juvenile ",
+    "Elder ",
+    "Greater ",
+    "Lesser ",
+    "Minor ",
+    "Major ",
+    "Elite ",
+];
+
+/// Traits that the bestiaries use for creature families with otherwise unrelated names, like golems and elementals.
+const FAMILY_TRAITS: [&str; 23] = [
+    "aeon",
+    "angel",
+    "archon",
+    "azata",
+    "daemon",
+    "demon",
+    "devil",
+    "dragon",
+    "elemental",
+    "genie",
+    "ghost",
+    "giant",
+    "golem",
+    "gremlin",
+    "hag",
+    "mummy",
+    "naga",
+    "protean",
+    "skeleton",
+    "sphinx",
+    "vampire",
+    "werecreature",
+    "zombie",
+];
+
+/// Creatures that are variants of each other, e.g. `Young Red Dragon` and `Ancient Red Dragon`,
+/// or that share a family trait. Name variants take precedence, so dragons are grouped by color
+/// and only the remaining creatures with the dragon trait form the `Dragon` family.
+#[derive(Default, Debug)]
+pub struct CreatureFamilies {
+    /// Family name by creature name
+    families: BTreeMap<String, String>,
+}
+
+impl CreatureFamilies {
+    pub fn new<'a>(creatures: impl IntoIterator<Item = &'a Creature>) -> Self {
+        let creatures = creatures.into_iter().collect_vec();
+        let mut families = BTreeMap::new();
+        let by_name = creatures.iter().into_group_map_by(|c| variant_family(&c.name));
+        for (family, members) in by_name {
+            if members.len() > 1 && members.iter().any(|c| c.name != family) {
+                for member in members {
+                    families.insert(member.name.clone(), family.clone());
+                }
+            }
+        }
+        for family_trait in FAMILY_TRAITS {
+            let members = creatures
+                .iter()
+                .filter(|c| !families.contains_key(&c.name) && c.traits.misc.iter().any(|t| t == family_trait))
+                .collect_vec();
+            if members.len() > 1 {
+                for member in members {
+                    families.insert(member.name.clone(), family_trait.to_case(Case::Title));
+                }
+            }
+        }
+        CreatureFamilies { families }
+    }
+
+    pub fn family(&self, creature: &Creature) -> Option<&str> {
+        self.families.get(&creature.name).map(String::as_str)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.families.values().map(String::as_str).sorted().dedup()
+    }
+}
+
+/// The name without parenthesized variants and age or strength prefixes.
+fn variant_family(name: &str) -> String {
+    let mut name = name.split(" (").next().unwrap_or(name);
+    while let Some(rest) = VARIANT_PREFIXES.iter().find_map(|prefix| name.strip_prefix(prefix)) {
+        name = rest;
+    }
+    name.to_string()
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Eq)]
 #[serde(from = "JsonHazard")]
 pub struct Hazard {
@@ -313,7 +407,7 @@ fn lowercased(xs: &[String]) -> Vec<String> {
         .collect()
 }
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, AsRefStr, Clone, Copy)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, AsRefStr, IntoStaticStr, Clone, Copy, EnumIter)]
 pub enum Alignment {
     LG,
     NG,
@@ -723,7 +817,7 @@ enum CreatureItemType {
 mod tests {
     use super::*;
     use crate::{
-        data::{action_type::ActionType, damage::DamageType},
+        data::{action_type::ActionType, actions::ActionBlock, damage::DamageType, outcomes::Outcomes},
         tests::read_test_file,
     };
     use pretty_assertions::assert_eq;
@@ -888,7 +982,9 @@ mod tests {
                     rarity: Rarity::Common,
                     alignment: None,
                     size: None
-                }
+                },
+                block: ActionBlock::default(),
+                outcomes: Outcomes::default(),
             },
             Action {
                 name: "Darkvision".to_string(),
@@ -900,7 +996,9 @@ mod tests {
                     rarity: Rarity::Common,
                     alignment: None,
                     size: None
-                }
+                },
+                block: ActionBlock::default(),
+                outcomes: Outcomes::default(),
             },
             Action {
                 name: "Scent (Imprecise) 60 feet".to_string(),
@@ -913,7 +1011,9 @@ mod tests {
                     rarity: Rarity::Common,
                     alignment: None,
                     size: None
-                }
+                },
+                block: ActionBlock::default(),
+                outcomes: Outcomes::default(),
             },
             Action {
                 name: "At-Will Spells".to_string(),
@@ -925,7 +1025,9 @@ mod tests {
                     rarity: Rarity::Common,
                     alignment: None,
                     size: None
-                }
+                },
+                block: ActionBlock::default(),
+                outcomes: Outcomes::default(),
             },
             Action {
                 name: "Dragon Heat".to_string(),
@@ -937,14 +1039,23 @@ mod tests {
                     rarity: Rarity::Common,
                     alignment: None,
                     size: None
-                }
+                },
+                block: ActionBlock::default(),
+                outcomes: Outcomes::default(),
             },
             Action {
                 name: "Frightful Presence".to_string(),
                 description: "<p>90 feet <a href=\"/creature_abilities/aura\">Aura</a> DC 40 will</p>\n<hr />\n<p><p>A creature that first enters the area must attempt a Will save.</p>\n<p>Regardless of the result of the saving throw, the creature is temporarily immune to this monster's Frightful Presence for 1 minute.</p>\n<hr />\n<p><strong>Critical Success</strong> The creature is unaffected by the presence.</p>\n<p><strong>Success</strong> The creature is <a href=\"/condition/frightened\">Frightened 1</a>.</p>\n<p><strong>Failure</strong> The creature is <a href=\"/condition/frightened\">Frightened 2</a>.</p>\n<p><strong>Critical Failure</strong> The creature is <a href=\"/condition/frightened\">Frightened 4</a>.</p></p>".to_string(),
                 action_type: ActionType::Passive,
                 number_of_actions: None,
-                traits: Traits { misc: vec!["aura".to_string(), "emotion".to_string(), "fear".to_string(), "mental".to_string()], rarity: Rarity::Common, alignment: None, size: None }
+                traits: Traits { misc: vec!["aura".to_string(), "emotion".to_string(), "fear".to_string(), "mental".to_string()], rarity: Rarity::Common, alignment: None, size: None },
+                block: ActionBlock::default(),
+                outcomes: Outcomes {
+                    critical_success: Some("The creature is unaffected by the presence.".to_string()),
+                    success: Some("The creature is <a href=\"/condition/frightened\">Frightened 1</a>.".to_string()),
+                    failure: Some("The creature is <a href=\"/condition/frightened\">Frightened 2</a>.".to_string()),
+                    critical_failure: Some("The creature is <a href=\"/condition/frightened\">Frightened 4</a>.".to_string()),
+                },
             },
             Action {
                 name: "Attack of Opportunity".to_string(),
@@ -955,7 +1066,13 @@ mod tests {
 <p><strong>Effect</strong> The monster attempts a melee Strike against the triggering creature. If the attack is a critical hit and the trigger was a manipulate action, the monster disrupts that action. This Strike doesn't count toward the monster's multiple attack penalty, and its multiple attack penalty doesn't apply to this Strike.</p></p>"#.to_string(),
                 action_type: ActionType::Reaction,
                 number_of_actions: None,
-                traits: Traits { misc: vec![], rarity: Rarity::Common, alignment: None, size: None }
+                traits: Traits { misc: vec![], rarity: Rarity::Common, alignment: None, size: None },
+                block: ActionBlock {
+                    trigger: Some("A creature within the monster's reach uses a manipulate action or a move action, makes a ranged attack, or leaves a square during a move action it's using.".to_string()),
+                    effect: Some("The monster attempts a melee Strike against the triggering creature. If the attack is a critical hit and the trigger was a manipulate action, the monster disrupts that action. This Strike doesn't count toward the monster's multiple attack penalty, and its multiple attack penalty doesn't apply to this Strike.".to_string()),
+                    ..ActionBlock::default()
+                },
+                outcomes: Outcomes::default(),
             },
 
             Action {
@@ -963,7 +1080,13 @@ mod tests {
                 description: "<p><strong>Trigger</strong> A creature within 100 feet casts a fire spell, or a fire spell otherwise comes into effect from a source within 100 feet.</p>\n<hr />\n<p><strong>Effect</strong> The dragon makes all the choices to determine the targets, destination, or other effects of the spell, as though it were the caster.</p>".to_string(),
                 action_type: ActionType::Reaction,
                 number_of_actions: None,
-                traits: Traits { misc: vec!["abjuration".to_string(), "arcane".to_string()], rarity: Rarity::Common, alignment: None, size: None }
+                traits: Traits { misc: vec!["abjuration".to_string(), "arcane".to_string()], rarity: Rarity::Common, alignment: None, size: None },
+                block: ActionBlock {
+                    trigger: Some("A creature within 100 feet casts a fire spell, or a fire spell otherwise comes into effect from a source within 100 feet.".to_string()),
+                    effect: Some("The dragon makes all the choices to determine the targets, destination, or other effects of the spell, as though it were the caster.".to_string()),
+                    ..ActionBlock::default()
+                },
+                outcomes: Outcomes::default(),
             },
             Action {
                 name: "+1 Status to All Saves vs. Magic".to_string(),
@@ -975,35 +1098,45 @@ mod tests {
                     rarity: Rarity::Common,
                     alignment: None,
                     size: None
-                }
+                },
+                block: ActionBlock::default(),
+                outcomes: Outcomes::default(),
             },
             Action {
                 name: "Breath Weapon".to_string(),
                 description: "<p>The dragon breathes a blast of flame that deals 20d6 fire damage in a 60-foot cone (DC 42 basic reflex save).</p>\n<p>It can't use Breath Weapon again for 1d4 rounds.</p>".to_string(),
                 action_type: ActionType::Action,
                 number_of_actions: Some(2),
-                traits: Traits { misc: vec!["arcane".to_string(), "evocation".to_string(), "fire".to_string()], rarity: Rarity::Common, alignment: None, size: None }
+                traits: Traits { misc: vec!["arcane".to_string(), "evocation".to_string(), "fire".to_string()], rarity: Rarity::Common, alignment: None, size: None },
+                block: ActionBlock::default(),
+                outcomes: Outcomes::default(),
             },
             Action {
                 name: "Draconic Frenzy".to_string(),
                 description: "<p>The dragon makes two claw Strikes and one wing Strike in any order.</p>".to_string(),
                 action_type: ActionType::Action,
                 number_of_actions: Some(2),
-                traits: Traits { misc: vec![], rarity: Rarity::Common, alignment: None, size: None }
+                traits: Traits { misc: vec![], rarity: Rarity::Common, alignment: None, size: None },
+                block: ActionBlock::default(),
+                outcomes: Outcomes::default(),
             },
             Action {
                 name: "Draconic Momentum".to_string(),
                 description: "<p>The dragon recharges its Breath Weapon whenever it scores a critical hit with a Strike.</p>".to_string(),
                 action_type: ActionType::Passive,
                 number_of_actions: None,
-                traits: Traits { misc: vec![], rarity: Rarity::Common, alignment: None, size: None }
+                traits: Traits { misc: vec![], rarity: Rarity::Common, alignment: None, size: None },
+                block: ActionBlock::default(),
+                outcomes: Outcomes::default(),
             },
             Action {
                 name: "Manipulate Flames".to_string(),
                 description: "<p>The red dragon attempts to take control of a magical fire or a fire spell within 100 feet.</p>\n<p>If it succeeds at a counteract check (counteract level 10, counteract modifier +32), the original caster loses control of the spell or magic fire, control is transferred to the dragon, and the dragon counts as having <a href=\"/action/sustain_a_spell\">Sustained the Spell</a> with this action (if applicable). The dragon can choose to end the spell instead of taking control, if it chooses.</p>".to_string(),
                 action_type: ActionType::Action,
                 number_of_actions: Some(1),
-                traits: Traits { misc: vec!["arcane".to_string(), "concentrate".to_string(), "transmutation".to_string()], rarity: Rarity::Common, alignment: None, size: None }
+                traits: Traits { misc: vec!["arcane".to_string(), "concentrate".to_string(), "transmutation".to_string()], rarity: Rarity::Common, alignment: None, size: None },
+                block: ActionBlock::default(),
+                outcomes: Outcomes::default(),
             }
         ];
         dargon.actions.sort();
@@ -1,6 +1,9 @@
-use super::{creature::Alignment, ValueWrapper};
+use super::{compendium_reference, creature::Alignment, skills::Skill, OneOrMany, ValueWrapper};
 use crate::text_cleanup;
+use convert_case::{Case, Casing};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, convert::TryFrom};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Eq)]
 #[serde(from = "JsonDeity")]
@@ -10,6 +13,29 @@ pub struct Deity {
     // Some meta deities are unaligned
     pub alignment: Option<Alignment>,
     pub follower_alignments: Vec<Alignment>,
+    pub divine_font: Vec<DivineFont>,
+    pub divine_skills: Vec<Skill>,
+    /// Names of the favored weapons, e.g. `Mace`
+    pub favored_weapons: Vec<String>,
+    pub domains: Vec<String>,
+    pub alternate_domains: Vec<String>,
+    /// Spell level and spell name
+    pub cleric_spells: Vec<(i32, String)>,
+    pub sanctification: Option<Sanctification>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, AsRefStr, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum DivineFont {
+    Harm,
+    Heal,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Sanctification {
+    /// `can` or `must`
+    pub modal: String,
+    pub what: Vec<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -22,6 +48,16 @@ struct JsonDeity {
 struct JsonDeityData {
     description: ValueWrapper<String>,
     alignment: JsonDeityAlignment,
+    #[serde(default)]
+    font: Vec<DivineFont>,
+    skill: Option<OneOrMany>,
+    #[serde(default)]
+    weapons: Vec<String>,
+    #[serde(default)]
+    domains: JsonDeityDomains,
+    #[serde(default)]
+    spells: BTreeMap<String, String>,
+    sanctification: Option<Sanctification>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -30,13 +66,47 @@ struct JsonDeityAlignment {
     follower: Vec<Alignment>,
 }
 
+#[derive(Deserialize, Debug, Default)]
+struct JsonDeityDomains {
+    #[serde(default)]
+    primary: Vec<String>,
+    #[serde(default)]
+    alternate: Vec<String>,
+}
+
+/// Slugs like `war-flail` are turned into names like `War Flail`.
+fn name_from_slug(slug: &str) -> String {
+    slug.from_case(Case::Kebab).to_case(Case::Title)
+}
+
 impl From<JsonDeity> for Deity {
     fn from(jd: JsonDeity) -> Self {
+        let domains = |slugs: Vec<String>| slugs.iter().map(|d| name_from_slug(d)).sorted().collect();
         Deity {
             content: text_cleanup(&jd.system.description.value),
             name: jd.name,
             alignment: jd.system.alignment.own,
             follower_alignments: jd.system.alignment.follower,
+            divine_font: jd.system.font,
+            divine_skills: jd
+                .system
+                .skill
+                .map(Vec::from)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|s| Skill::try_from(name_from_slug(s).as_str()).ok())
+                .collect(),
+            favored_weapons: jd.system.weapons.iter().map(|w| name_from_slug(w)).collect(),
+            domains: domains(jd.system.domains.primary),
+            alternate_domains: domains(jd.system.domains.alternate),
+            cleric_spells: jd
+                .system
+                .spells
+                .iter()
+                .filter_map(|(level, uuid)| Some((level.parse().ok()?, compendium_reference(uuid)?.1.to_string())))
+                .sorted()
+                .collect(),
+            sanctification: jd.system.sanctification,
         }
     }
 }
@@ -51,4 +121,32 @@ mod test {
         let asmodeus: Deity = serde_json::from_str(&read_test_file("deities.db/asmodeus.json")).expect("Deserialization failed");
         assert_eq!(asmodeus.name, String::from("Asmodeus"));
     }
+
+    #[test]
+    fn should_deserialize_deity_details() {
+        let json = r#"{
+            "name": "Gorum",
+            "system": {
+                "description": { "value": "<p>Our Lord in Iron</p>" },
+                "alignment": { "own": "CN", "follower": ["CN", "CE"] },
+                "font": ["harm", "heal"],
+                "skill": "athletics",
+                "weapons": ["greatsword"],
+                "domains": { "primary": ["strength", "confidence", "destruction", "zeal"], "alternate": ["might"] },
+                "spells": { "4": "Compendium.pf2e.spells-srd.Item.Weapon Storm", "1": "Compendium.pf2e.spells-srd.True Strike" },
+                "sanctification": { "modal": "can", "what": ["unholy"] }
+            }
+        }"#;
+        let gorum: Deity = serde_json::from_str(json).expect("Deserialization failed");
+        assert_eq!(gorum.divine_font, vec![DivineFont::Harm, DivineFont::Heal]);
+        assert_eq!(gorum.divine_skills, vec![Skill::Athletics]);
+        assert_eq!(gorum.favored_weapons, vec![String::from("Greatsword")]);
+        assert_eq!(gorum.domains, vec!["Confidence", "Destruction", "Strength", "Zeal"]);
+        assert_eq!(gorum.alternate_domains, vec!["Might"]);
+        assert_eq!(
+            gorum.cleric_spells,
+            vec![(1, String::from("True Strike")), (4, String::from("Weapon Storm"))]
+        );
+        assert_eq!(gorum.sanctification.map(|s| s.modal), Some(String::from("can")));
+    }
 }
@@ -1,12 +1,21 @@
 use super::{
+    crafting::split_craft_requirements,
     damage::{DamageType, Die, EquipmentDamage},
+    rules::Rule,
     traits::{JsonTraits, Traits},
     HasName, ValueWrapper,
 };
 use crate::text_cleanup;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use std::{cmp::Ordering, fmt, fmt::Display};
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    fmt,
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, Mul},
+};
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Eq)]
 #[serde(from = "JsonEquipment")]
@@ -27,6 +36,83 @@ pub struct Equipment {
     pub weight: Weight,
     pub item_type: ItemType,
     pub source: String,
+    #[serde(skip_serializing)]
+    pub rules: Vec<Rule>,
+    pub armor: Option<ArmorStats>,
+    pub shield: Option<ShieldStats>,
+    /// Interact actions needed to reload, `None` for weapons that don’t need reloading
+    pub reload: Option<i32>,
+    /// Only part of the description in Foundry, so it’s moved here to be shown with the other crafting info
+    pub craft_requirements: Option<String>,
+}
+
+impl Equipment {
+    /// Foundry doesn’t say which ammunition a weapon uses, so we guess from the group.
+    pub fn ammunition(&self) -> Option<&'static str> {
+        if self.item_type != ItemType::Weapon || self.range == 0 {
+            return None;
+        }
+        match self.group {
+            WeaponGroup::Bow if self.name.to_lowercase().contains("crossbow") => Some("Bolts"),
+            WeaponGroup::Bow => Some("Arrows"),
+            WeaponGroup::Sling => Some("Sling Bullets"),
+            WeaponGroup::Firearm => Some("Rounds"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Eq, Default)]
+pub struct ArmorStats {
+    pub ac_bonus: i32,
+    /// Some unarmored items like explorer’s clothing don’t have a cap
+    pub dex_cap: Option<i32>,
+    pub check_penalty: i32,
+    /// In feet
+    pub speed_penalty: i32,
+    pub strength: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Eq, Default)]
+pub struct ShieldStats {
+    pub ac_bonus: i32,
+    /// In feet
+    pub speed_penalty: i32,
+}
+
+/// Items that only differ by their grade, e.g. `Healing Potion (Lesser)` and `Healing Potion (Major)`, including the
+/// ungraded item like `Striking` next to `Striking (Greater)`.
+#[derive(Default, Debug)]
+pub struct ItemFamilies {
+    /// Number of variants by family name
+    families: BTreeMap<String, usize>,
+}
+
+impl ItemFamilies {
+    pub fn new<'a>(items: impl IntoIterator<Item = &'a Equipment>) -> Self {
+        let families = items
+            .into_iter()
+            .into_group_map_by(|i| i.without_variant())
+            .into_iter()
+            .filter(|(family, members)| members.len() > 1 && members.iter().any(|i| &i.name != family))
+            .map(|(family, members)| (family.to_string(), members.len()))
+            .collect();
+        ItemFamilies { families }
+    }
+
+    /// The family name if the item is one of several variants.
+    pub fn family<'a>(&self, item: &'a Equipment) -> Option<&'a str> {
+        let name = item.without_variant();
+        self.families.contains_key(name).then_some(name)
+    }
+
+    pub fn variants(&self, family: &str) -> usize {
+        self.families.get(family).copied().unwrap_or_default()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.families.keys().map(String::as_str)
+    }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Eq, Default)]
@@ -41,6 +127,59 @@ pub struct Price {
     pp: u32,
 }
 
+impl Price {
+    pub fn as_copper(&self) -> u32 {
+        self.cp + self.sp * 10 + self.gp * 100 + self.pp * 1000
+    }
+
+    /// Prices are given in gold in the books, so platinum is never used here.
+    pub fn from_copper(copper: u32) -> Self {
+        Price {
+            cp: copper % 10,
+            sp: copper / 10 % 10,
+            gp: copper / 100,
+            pp: 0,
+        }
+    }
+}
+
+/// Prices are compared by their value in copper, `1 pp` and `10 gp` only tie-break on the coins.
+impl Ord for Price {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_copper()
+            .cmp(&other.as_copper())
+            .then_with(|| (self.pp, self.gp, self.sp, self.cp).cmp(&(other.pp, other.gp, other.sp, other.cp)))
+    }
+}
+
+impl PartialOrd for Price {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Price {
+    type Output = Price;
+
+    fn add(self, other: Price) -> Price {
+        Price::from_copper(self.as_copper() + other.as_copper())
+    }
+}
+
+impl Mul<u32> for Price {
+    type Output = Price;
+
+    fn mul(self, factor: u32) -> Price {
+        Price::from_copper(self.as_copper() * factor)
+    }
+}
+
+impl Sum for Price {
+    fn sum<I: Iterator<Item = Price>>(iter: I) -> Price {
+        Price::from_copper(iter.map(|p| p.as_copper()).sum())
+    }
+}
+
 impl Display for Price {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.pp != 0 {
@@ -170,12 +309,61 @@ impl From<Option<JsonWeight>> for Weight {
     }
 }
 
+/// Armor and shield values used to be wrapped in objects and were sometimes strings.
+/// Parsed leniently because the old format is inconsistent between item types.
+type LegacyNumber = Option<serde_json::Value>;
+
+fn legacy_number(n: LegacyNumber) -> Option<i32> {
+    match n? {
+        serde_json::Value::Number(n) => n.as_i64().map(|n| n as i32),
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Object(mut o) => legacy_number(o.remove("value")),
+        _ => None,
+    }
+}
+
+impl JsonEquipmentData {
+    fn ac_bonus(&mut self) -> i32 {
+        self.ac_bonus.or_else(|| legacy_number(self.armor.take())).unwrap_or(0)
+    }
+
+    fn speed_penalty(&mut self) -> i32 {
+        self.speed_penalty.or_else(|| legacy_number(self.speed.take())).unwrap_or(0)
+    }
+}
+
 impl From<JsonEquipment> for Equipment {
-    fn from(je: JsonEquipment) -> Self {
+    fn from(mut je: JsonEquipment) -> Self {
+        let category = je.system.category.unwrap_or(ProficiencyGroup::NoProficiency);
+        let (armor, shield) = match (je.item_type, category) {
+            (ItemType::Shield, _) | (ItemType::Armor, ProficiencyGroup::Shield) => (
+                None,
+                Some(ShieldStats {
+                    ac_bonus: je.system.ac_bonus(),
+                    speed_penalty: je.system.speed_penalty(),
+                }),
+            ),
+            (ItemType::Armor, _) => (
+                Some(ArmorStats {
+                    ac_bonus: je.system.ac_bonus(),
+                    dex_cap: je.system.dex_cap.or_else(|| legacy_number(je.system.dex.take())),
+                    check_penalty: je
+                        .system
+                        .check_penalty
+                        .or_else(|| legacy_number(je.system.check.take()))
+                        .unwrap_or(0),
+                    speed_penalty: je.system.speed_penalty(),
+                    strength: legacy_number(je.system.strength.take()).filter(|&s| s != 0),
+                }),
+                None,
+            ),
+            _ => (None, None),
+        };
+        let (description, craft_requirements) = split_craft_requirements(&text_cleanup(&je.system.description.value));
         Equipment {
             name: je.name.clone(),
             damage: je.system.damage.map(EquipmentDamage::from),
-            description: text_cleanup(&je.system.description.value),
+            description,
             group: je.system.group.and_then(WrappedOrNot::value).unwrap_or(WeaponGroup::NotAWeapon),
             hardness: je.system.hardness,
             max_hp: je.system.hp.map(|hp| hp.max).unwrap_or(0),
@@ -183,12 +371,20 @@ impl From<JsonEquipment> for Equipment {
             price: je.system.price.value,
             range: je.system.range.and_then(WrappedOrNot::value).map(i32::from).unwrap_or(0),
             splash_damage: je.system.splash_damage.value.map(|v| v.into()).unwrap_or(0),
-            usage: je.system.traits.usage.map(|v| v.value),
+            usage: je.system.traits.usage.take().map(|v| v.value).or_else(|| {
+                // Newer data has more usages than we know, those are ignored
+                je.system.usage.take().and_then(|u| serde_json::from_value(u.value).ok())
+            }),
             traits: Traits::from(je.system.traits),
-            category: je.system.category.unwrap_or(ProficiencyGroup::NoProficiency),
+            category,
             weight: je.system.weight.map(|v| v.value).into(),
             item_type: je.item_type,
             source: je.system.source.value,
+            rules: je.system.rules,
+            armor,
+            shield,
+            reload: legacy_number(je.system.reload.take()),
+            craft_requirements,
         }
     }
 }
@@ -249,6 +445,23 @@ struct JsonEquipmentData {
     weight: Option<ValueWrapper<JsonWeight>>,
     value: Option<ValueWrapper<i32>>,
     source: ValueWrapper<String>,
+    #[serde(default)]
+    rules: Vec<Rule>,
+    // Armor and shields in the current format
+    ac_bonus: Option<i32>,
+    dex_cap: Option<i32>,
+    check_penalty: Option<i32>,
+    speed_penalty: Option<i32>,
+    strength: LegacyNumber,
+    // Armor and shields in the old format
+    armor: LegacyNumber,
+    dex: LegacyNumber,
+    check: LegacyNumber,
+    speed: LegacyNumber,
+    // `-` for weapons that don’t need reloading
+    reload: LegacyNumber,
+    // Moved here from the traits
+    usage: Option<ValueWrapper<serde_json::Value>>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Eq, Clone, Copy)]
@@ -263,12 +476,14 @@ pub enum ItemType {
     Equipment,
     Treasure,
     Armor,
+    Shield,
     Backpack,
     Kit,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Eq, Clone, Copy)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Eq, Clone, Copy, AsRefStr)]
 #[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
 pub enum ItemUsage {
     HeldInOneHand,
     HeldInTwoHands,
@@ -324,7 +539,28 @@ pub enum ProficiencyGroup {
     NoProficiency,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Eq, Clone, Copy, AsRefStr)]
+impl ItemUsage {
+    /// Readable text like `held in one hand` or `worn cloak`
+    pub fn text(&self) -> String {
+        match self.as_ref() {
+            "worn" => String::from("worn"),
+            u => match u.strip_prefix("worn") {
+                Some(item) => format!("worn {}", item),
+                None => u.replace('-', " "),
+            },
+        }
+    }
+
+    pub fn hands(&self) -> Option<i32> {
+        match self {
+            ItemUsage::HeldInOneHand => Some(1),
+            ItemUsage::HeldInTwoHands => Some(2),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Eq, Clone, Copy, AsRefStr, EnumIter, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
 pub enum WeaponGroup {
     Knife,
@@ -350,6 +586,31 @@ pub enum WeaponGroup {
     #[serde(alias = "")]
     NotAWeapon,
     Cloth,
+    Skeletal,
+    Wood,
+}
+
+impl WeaponGroup {
+    /// The effect of a critical hit when you have access to the critical specialization of the group.
+    /// Armor groups are handled separately by the armor specialization rules and return `None`.
+    pub fn critical_specialization(&self) -> Option<&'static str> {
+        Some(match self {
+            WeaponGroup::Axe => "Choose one creature adjacent to the initial target and within reach. If its AC is lower than your attack roll result for the critical hit, you deal damage to that creature equal to the result of the weapon damage die you rolled (including extra dice for its potency rune, if any). This amount isn’t doubled, and no bonuses or other additional dice apply to this damage.",
+            WeaponGroup::Bomb => "Increase the radius of the bomb’s splash damage (if any) to 10 feet.",
+            WeaponGroup::Bow => "If the target of the critical hit is adjacent to a surface, it gets stuck to that surface by the missile. The target is immobilized and must spend an Interact action to attempt a DC 10 Athletics check to pull the missile free; it can’t move from its space until it succeeds. The creature doesn’t become stuck if it is incorporeal, is liquid, or could otherwise escape without effort.",
+            WeaponGroup::Brawling => "The target must succeed at a Fortitude save against your class DC or be slowed 1 until the end of your next turn.",
+            WeaponGroup::Club => "You knock the target away from you up to 10 feet (you choose the distance). This is forced movement.",
+            WeaponGroup::Dart | WeaponGroup::Knife => "The target takes 1d6 persistent bleed damage. You gain an item bonus to this bleed damage equal to the weapon’s item bonus to attack rolls.",
+            WeaponGroup::Firearm | WeaponGroup::Sling => "The target must succeed at a Fortitude save against your class DC or be stunned 1.",
+            WeaponGroup::Flail | WeaponGroup::Hammer => "The target is knocked prone.",
+            WeaponGroup::Pick => "The weapon viciously pierces the target, who takes 2 additional damage per weapon damage die.",
+            WeaponGroup::Polearm => "The target is moved 5 feet in a direction of your choice. This is forced movement.",
+            WeaponGroup::Shield => "You knock the target back from you 5 feet. This is forced movement.",
+            WeaponGroup::Spear => "The weapon pierces the target, weakening its attacks. The target is clumsy 1 until the start of your next turn.",
+            WeaponGroup::Sword => "The target is made flat-footed until the start of your next turn.",
+            _ => return None,
+        })
+    }
 }
 
 #[derive(Deserialize, PartialEq, Debug)]
@@ -424,6 +685,171 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_deserialize_armor_in_both_formats() {
+        let old: Equipment = serde_json::from_str(
+            r#"{
+                "name": "Chain Mail",
+                "type": "armor",
+                "system": {
+                    "description": { "value": "" },
+                    "source": { "value": "" },
+                    "traits": { "value": ["flexible", "noisy"] },
+                    "category": "medium",
+                    "group": { "value": "chain" },
+                    "armor": { "value": 4 },
+                    "dex": { "value": "1" },
+                    "check": { "value": -2 },
+                    "speed": { "value": -5 },
+                    "strength": { "value": 16 }
+                }
+            }"#,
+        )
+        .expect("Deserialization failed");
+        let new: Equipment = serde_json::from_str(
+            r#"{
+                "name": "Chain Mail",
+                "type": "armor",
+                "system": {
+                    "description": { "value": "" },
+                    "source": { "value": "" },
+                    "traits": { "value": ["flexible", "noisy"] },
+                    "category": "medium",
+                    "group": "chain",
+                    "acBonus": 4,
+                    "dexCap": 1,
+                    "checkPenalty": -2,
+                    "speedPenalty": -5,
+                    "strength": 16
+                }
+            }"#,
+        )
+        .expect("Deserialization failed");
+        let expected = ArmorStats {
+            ac_bonus: 4,
+            dex_cap: Some(1),
+            check_penalty: -2,
+            speed_penalty: -5,
+            strength: Some(16),
+        };
+        assert_eq!(old.armor, Some(expected));
+        assert_eq!(new.armor, Some(expected));
+        assert_eq!(new.group, WeaponGroup::Chain);
+        assert_eq!(new.shield, None);
+    }
+
+    #[test]
+    fn should_deserialize_shields_in_both_formats() {
+        let old: Equipment = serde_json::from_str(
+            r#"{
+                "name": "Tower Shield",
+                "type": "armor",
+                "system": {
+                    "description": { "value": "" },
+                    "source": { "value": "" },
+                    "traits": { "value": [] },
+                    "category": "shield",
+                    "armor": { "value": 2 },
+                    "speed": { "value": -5 },
+                    "hardness": 5,
+                    "hp": { "max": 20 }
+                }
+            }"#,
+        )
+        .expect("Deserialization failed");
+        let new: Equipment = serde_json::from_str(
+            r#"{
+                "name": "Tower Shield",
+                "type": "shield",
+                "system": {
+                    "description": { "value": "" },
+                    "source": { "value": "" },
+                    "traits": { "value": [] },
+                    "acBonus": 2,
+                    "speedPenalty": -5,
+                    "hardness": 5,
+                    "hp": { "max": 20 }
+                }
+            }"#,
+        )
+        .expect("Deserialization failed");
+        let expected = Some(ShieldStats {
+            ac_bonus: 2,
+            speed_penalty: -5,
+        });
+        assert_eq!(old.shield, expected);
+        assert_eq!(new.shield, expected);
+        assert_eq!(old.armor, None);
+        assert_eq!(new.max_hp, 20);
+    }
+
+    #[test]
+    fn should_calculate_with_prices() {
+        let gp = |gp| Price { gp, ..Default::default() };
+        let potion = Price {
+            sp: 5,
+            cp: 3,
+            ..Default::default()
+        };
+        let ring = Price {
+            pp: 2,
+            gp: 5,
+            ..Default::default()
+        };
+        assert_eq!(potion.as_copper(), 53);
+        assert_eq!(ring.as_copper(), 2500);
+        assert_eq!(Price::from_copper(ring.as_copper()), gp(25));
+        assert_eq!((potion + ring).to_string(), "25 gp 5 sp 3 cp ");
+        assert_eq!(potion * 20, Price::from_copper(1060));
+        assert_eq!([potion, potion, ring].into_iter().sum::<Price>(), Price::from_copper(2606));
+        assert!(potion < ring);
+        assert!(
+            gp(10)
+                < Price {
+                    pp: 1,
+                    ..Default::default()
+                }
+        );
+        assert!(
+            gp(11)
+                > Price {
+                    pp: 1,
+                    ..Default::default()
+                }
+        );
+    }
+
+    #[test]
+    fn should_describe_usage() {
+        assert_eq!(ItemUsage::HeldInOneHand.text(), "held in one hand");
+        assert_eq!(ItemUsage::Worncloak.text(), "worn cloak");
+        assert_eq!(ItemUsage::Worn.text(), "worn");
+        assert_eq!(ItemUsage::HeldInTwoHands.hands(), Some(2));
+        assert_eq!(ItemUsage::Worn.hands(), None);
+    }
+
+    #[test]
+    fn should_group_variants_into_families() {
+        let item = |name: &str| crate::tests::equipment(name, "consumable", 0, 0, "common", &[], serde_json::json!({}));
+        let items = [
+            item("Healing Potion (Lesser)"),
+            item("Healing Potion (Major)"),
+            item("Bag of Holding (Type I)"),
+            item("Healing Potion"),
+            item("Flaming"),
+            item("Flaming (Greater)"),
+            item("Rope"),
+        ];
+        let families = ItemFamilies::new(&items);
+        assert_eq!(families.family(&items[0]), Some("Healing Potion"));
+        assert_eq!(families.family(&items[2]), None);
+        assert_eq!(families.family(&items[3]), Some("Healing Potion"));
+        assert_eq!(families.variants("Healing Potion"), 3);
+        assert_eq!(families.family(&items[4]), Some("Flaming"));
+        assert_eq!(families.variants("Flaming"), 2);
+        assert_eq!(families.family(&items[6]), None);
+    }
+
     #[test]
     fn test_digit_grouping() {
         assert_eq!(group_digits(1), "1");
@@ -1,6 +1,10 @@
 use super::{
     action_type::ActionType,
+    actions::ActionBlock,
     feat_type::FeatType,
+    outcomes::Outcomes,
+    prerequisites::Prerequisite,
+    rules::Rule,
     traits::{JsonTraits, Traits},
     HasLevel, ValueWrapper,
 };
@@ -14,11 +18,15 @@ pub struct Feat {
     pub action_type: ActionType,
     pub actions: Option<i32>,
     pub description: String,
+    pub block: ActionBlock,
+    pub outcomes: Outcomes,
     pub feat_type: FeatType,
     pub level: i32,
-    pub prerequisites: Vec<String>,
+    pub prerequisites: Vec<Prerequisite>,
     pub traits: Traits,
     pub source: String,
+    #[serde(skip_serializing)]
+    pub rules: Vec<Rule>,
 }
 
 impl HasLevel for Feat {
@@ -29,16 +37,20 @@ impl HasLevel for Feat {
 
 impl From<JsonFeat> for Feat {
     fn from(jf: JsonFeat) -> Self {
+        let description = text_cleanup(&jf.system.description.value);
         Feat {
             name: jf.name.clone(),
             action_type: jf.system.action_type.value,
             actions: jf.system.actions.value.filter(|&n| n != 0),
-            description: text_cleanup(&jf.system.description.value),
+            block: ActionBlock::parse(&description),
+            outcomes: Outcomes::parse(&description),
+            description,
             feat_type: jf.system.feat_type.value,
             level: jf.system.level.value,
-            prerequisites: jf.system.prerequisites.value.into_iter().map(|p| p.value).collect(),
+            prerequisites: jf.system.prerequisites.value.into_iter().map(|p| p.value.into()).collect(),
             traits: jf.system.traits.into(),
             source: jf.system.source.value,
+            rules: jf.system.rules,
         }
     }
 }
@@ -60,6 +72,8 @@ struct JsonFeatData {
     prerequisites: ValueWrapper<Vec<ValueWrapper<String>>>,
     traits: JsonTraits,
     source: ValueWrapper<String>,
+    #[serde(default)]
+    rules: Vec<Rule>,
 }
 
 #[cfg(test)]
@@ -97,8 +111,12 @@ mod tests {
             serde_json::from_str(&read_test_file("feats.db/champion-dedication.json")).expect("Deserialization failed");
         assert_eq!("Champion Dedication", champion_dedication.name.as_str());
         assert_eq!(
-            vec![String::from("Strength 14"), String::from("Charisma 14")],
-            champion_dedication.prerequisites,
+            vec!["Strength 14", "Charisma 14"],
+            champion_dedication
+                .prerequisites
+                .iter()
+                .map(|p| p.text.as_str())
+                .collect::<Vec<_>>(),
         );
     }
 }
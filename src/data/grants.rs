@@ -0,0 +1,172 @@
+use super::{rules::HasRules, HasName, ObjectName};
+use std::collections::HashMap;
+
+/// A page on the website, identified by its category (the first part of the url) and name.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Reference {
+    pub category: &'static str,
+    pub name: String,
+}
+
+impl HasName for Reference {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The target of a `GrantItem` rule. Targets that aren’t part of any loaded compendium don’t have a category.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grant {
+    pub category: Option<&'static str>,
+    pub name: String,
+}
+
+type Key = (&'static str, String);
+
+/// Which items grant which other items, resolved in both directions.
+#[derive(Debug, Default)]
+pub struct Grants {
+    known: HashMap<Key, String>,
+    grants: HashMap<Key, Vec<Grant>>,
+    granted_by: HashMap<Key, Vec<Reference>>,
+}
+
+impl Grants {
+    /// Registers all elements of a compendium so grants pointing at them can be resolved.
+    pub fn add_targets<T: HasName>(&mut self, category: &'static str, elements: &[T]) {
+        for e in elements {
+            self.known.insert((category, e.url_name()), e.name().to_string());
+        }
+    }
+
+    /// Collects the grants of all elements of a compendium.
+    /// Targets have to be added before this, otherwise the grants won’t be linked.
+    pub fn add_sources<T: HasName + HasRules>(&mut self, category: &'static str, elements: &[T]) {
+        for e in elements {
+            let grants = e
+                .rules()
+                .iter()
+                .filter_map(|r| r.granted_item())
+                // Effects don’t have pages and are mostly toggles for the Foundry UI.
+                .filter(|(pack, _)| !pack.ends_with("-effects"))
+                .map(|(pack, name)| self.resolve(pack, name))
+                .collect::<Vec<_>>();
+            for grant in &grants {
+                if let Some(target_category) = grant.category {
+                    self.granted_by
+                        .entry((target_category, ObjectName(&grant.name).url_name()))
+                        .or_default()
+                        .push(Reference {
+                            category,
+                            name: e.name().to_string(),
+                        });
+                }
+            }
+            if !grants.is_empty() {
+                self.grants.insert((category, e.url_name()), grants);
+            }
+        }
+    }
+
+    fn resolve(&self, pack: &str, name: &str) -> Grant {
+        category_for_pack(pack)
+            .and_then(|category| {
+                self.known.get(&(category, ObjectName(name).url_name())).map(|known_name| Grant {
+                    category: Some(category),
+                    name: known_name.clone(),
+                })
+            })
+            .unwrap_or_else(|| Grant {
+                category: None,
+                name: name.to_string(),
+            })
+    }
+
+    pub fn grants<T: HasName>(&self, category: &'static str, element: &T) -> &[Grant] {
+        self.grants
+            .get(&(category, element.url_name()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn granted_by<T: HasName>(&self, category: &'static str, element: &T) -> &[Reference] {
+        self.granted_by
+            .get(&(category, element.url_name()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+fn category_for_pack(pack: &str) -> Option<&'static str> {
+    match pack {
+        "feats-srd" => Some("feat"),
+        "spells-srd" => Some("spell"),
+        "equipment-srd" => Some("item"),
+        "actionspf2e" => Some("action"),
+        "classfeatures" => Some("classfeature"),
+        "ancestryfeatures" => Some("ancestryfeature"),
+        "heritages" => Some("heritage"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::rules::Rule;
+
+    struct Source {
+        name: &'static str,
+        rules: Vec<Rule>,
+    }
+
+    impl HasName for Source {
+        fn name(&self) -> &str {
+            self.name
+        }
+    }
+
+    impl HasRules for Source {
+        fn rules(&self) -> &[Rule] {
+            &self.rules
+        }
+    }
+
+    #[test]
+    fn should_resolve_grants_in_both_directions() {
+        let grant = |uuid: &str| Rule::GrantItem { uuid: uuid.to_string() };
+        let heritage = Source {
+            name: "Skilled Heritage",
+            rules: vec![
+                grant("Compendium.pf2e.feats-srd.Item.Assurance"),
+                grant("Compendium.pf2e.feat-effects.Effect: Skilled"),
+                grant("Compendium.pf2e.feats-srd.Homebrew Feat"),
+            ],
+        };
+        let mut grants = Grants::default();
+        grants.add_targets("feat", &[ObjectName("Assurance")]);
+        grants.add_sources("heritage", &[heritage]);
+
+        assert_eq!(
+            grants.grants("heritage", &ObjectName("Skilled Heritage")),
+            &[
+                Grant {
+                    category: Some("feat"),
+                    name: String::from("Assurance"),
+                },
+                Grant {
+                    category: None,
+                    name: String::from("Homebrew Feat"),
+                },
+            ]
+        );
+        assert_eq!(
+            grants.granted_by("feat", &ObjectName("Assurance")),
+            &[Reference {
+                category: "heritage",
+                name: String::from("Skilled Heritage"),
+            }]
+        );
+        assert!(grants.granted_by("feat", &ObjectName("Homebrew Feat")).is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{traits::Traits, ValueWrapper};
+use super::{rules::Rule, traits::Traits, ValueWrapper};
 use crate::{data::traits::JsonTraits, text_cleanup};
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +10,8 @@ pub struct Heritage {
     pub ancestry: Option<String>,
     pub traits: Traits,
     pub source: String,
+    #[serde(skip_serializing)]
+    pub rules: Vec<Rule>,
 }
 
 impl From<JsonHeritage> for Heritage {
@@ -20,6 +22,7 @@ impl From<JsonHeritage> for Heritage {
             description: text_cleanup(&jh.system.description.value),
             traits: jh.system.traits.into(),
             source: jh.system.source.value,
+            rules: jh.system.rules,
         }
     }
 }
@@ -37,6 +40,8 @@ pub struct InnerJsonHeritage {
     traits: JsonTraits,
     source: ValueWrapper<String>,
     ancestry: Option<JsonHeritageAncestry>,
+    #[serde(default)]
+    rules: Vec<Rule>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
@@ -7,8 +7,10 @@ use self::{
     conditions::Condition,
     creature::{Creature, Npc},
     deities::Deity,
+    equipment::Equipment,
     feats::Feat,
     heritages::Heritage,
+    rules::{HasRules, Rule},
     spells::Spell,
 };
 use crate::data::ancestry_features::AncestryFeature;
@@ -25,20 +27,29 @@ pub mod ancestry_features;
 pub mod backgrounds;
 pub mod boons_and_curses;
 pub mod class_features;
+pub mod class_progression;
 pub mod classes;
 pub mod conditions;
+pub mod crafting;
 pub mod creature;
 pub mod damage;
 pub mod deities;
 pub mod equipment;
 pub mod feat_type;
 pub mod feats;
+pub mod grants;
 pub mod heritages;
+pub mod outcomes;
+pub mod prerequisites;
 pub mod proficiency;
+pub mod rules;
+pub mod runes;
 pub mod size;
 pub mod skills;
+pub mod spell_items;
 pub mod spells;
 pub mod traits;
+pub mod treasure;
 
 lazy_static! {
     static ref URL_REPLACE_CHARACTERS: Regex = Regex::new("[ -]+").unwrap();
@@ -56,6 +67,34 @@ impl<T> From<T> for ValueWrapper<T> {
     }
 }
 
+/// Some fields are either a single string or a list depending on the version of the data.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub(crate) enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<OneOrMany> for Vec<String> {
+    fn from(o: OneOrMany) -> Self {
+        match o {
+            OneOrMany::One(s) => vec![s],
+            OneOrMany::Many(v) => v,
+        }
+    }
+}
+
+/// The compendium and name of an item referenced by a uuid like `Compendium.pf2e.spells-srd.Item.Heal`.
+pub fn compendium_reference(uuid: &str) -> Option<(&str, &str)> {
+    let (pack, name) = uuid.strip_prefix("Compendium.")?.trim_start_matches("pf2e.").split_once('.')?;
+    Some((pack, name.trim_start_matches("Item.")))
+}
+
+/// Item and creature families share one page named after the family.
+pub fn family_url_name(family: &str) -> String {
+    format!("family_{}", ObjectName(family).url_name())
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct ObjectName<'a>(pub &'a str);
 
@@ -155,6 +194,16 @@ macro_rules! has_name {
     };
 }
 
+macro_rules! has_rules {
+    ($type:ty) => {
+        impl HasRules for $type {
+            fn rules(&self) -> &[Rule] {
+                &self.rules
+            }
+        }
+    };
+}
+
 macro_rules! ord_by_name_and_level {
     ($type:ty) => {
         impl PartialOrd for $type {
@@ -188,6 +237,11 @@ has_name!(Feat);
 has_level!(Creature);
 has_name!(Creature);
 ord_by_name_and_level!(Spell);
+has_rules!(Feat);
+has_rules!(Equipment);
+has_rules!(ClassFeature);
+has_rules!(AncestryFeature);
+has_rules!(Heritage);
 
 #[cfg(test)]
 mod tests {
@@ -215,4 +269,9 @@ mod tests {
         let higher = NamedWithLevel { name: "BBB", level: 10 };
         assert!(lower < higher);
     }
+
+    #[test]
+    fn should_name_family_pages() {
+        assert_eq!(family_url_name("Healing Potion"), "family_healing_potion");
+    }
 }
@@ -0,0 +1,102 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+lazy_static! {
+    static ref OUTCOME_PARAGRAPH_REGEX: Regex =
+        Regex::new(r"<p>\s*<strong>(Critical Success|Success|Failure|Critical Failure)</strong>\s*(.*?)\s*</p>").unwrap();
+}
+
+/// What happens on each degree of success of a check or saving throw.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Eq, Default)]
+pub struct Outcomes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub critical_success: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub critical_failure: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, AsRefStr, EnumIter)]
+pub enum Degree {
+    #[strum(serialize = "Critical Success")]
+    CriticalSuccess,
+    Success,
+    Failure,
+    #[strum(serialize = "Critical Failure")]
+    CriticalFailure,
+}
+
+impl Outcomes {
+    pub fn parse(description: &str) -> Self {
+        let mut outcomes = Outcomes::default();
+        for (degree, _, text) in outcome_paragraphs(description) {
+            *outcomes.get_mut(degree) = Some(text.to_string());
+        }
+        outcomes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Outcomes::default()
+    }
+
+    pub fn get(&self, degree: Degree) -> Option<&str> {
+        match degree {
+            Degree::CriticalSuccess => &self.critical_success,
+            Degree::Success => &self.success,
+            Degree::Failure => &self.failure,
+            Degree::CriticalFailure => &self.critical_failure,
+        }
+        .as_deref()
+    }
+
+    fn get_mut(&mut self, degree: Degree) -> &mut Option<String> {
+        match degree {
+            Degree::CriticalSuccess => &mut self.critical_success,
+            Degree::Success => &mut self.success,
+            Degree::Failure => &mut self.failure,
+            Degree::CriticalFailure => &mut self.critical_failure,
+        }
+    }
+}
+
+/// The first paragraph for each degree of success in the order they appear in the description.
+pub fn outcome_paragraphs(description: &str) -> Vec<(Degree, Range<usize>, &str)> {
+    let mut paragraphs: Vec<(Degree, Range<usize>, &str)> = Vec::new();
+    for captures in OUTCOME_PARAGRAPH_REGEX.captures_iter(description) {
+        let degree = match &captures[1] {
+            "Critical Success" => Degree::CriticalSuccess,
+            "Success" => Degree::Success,
+            "Failure" => Degree::Failure,
+            _ => Degree::CriticalFailure,
+        };
+        if paragraphs.iter().all(|(d, _, _)| *d != degree) {
+            let text = captures.get(2).map(|m| m.as_str()).unwrap_or_default();
+            paragraphs.push((degree, captures.get(0).unwrap().range(), text));
+        }
+    }
+    paragraphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_outcomes() {
+        let description = "<p>Attempt a check.</p>\n<hr />\n<p><strong>Critical Success</strong> You grant a +2 bonus.</p>\n<p><strong>Success</strong> You grant a +1 bonus.</p>\n<p><strong>Critical Failure</strong> Your ally takes a <a href=\"/condition/clumsy\">-1 penalty</a>.</p>";
+        let outcomes = Outcomes::parse(description);
+        assert_eq!(outcomes.get(Degree::CriticalSuccess), Some("You grant a +2 bonus."));
+        assert_eq!(outcomes.get(Degree::Success), Some("You grant a +1 bonus."));
+        assert_eq!(outcomes.get(Degree::Failure), None);
+        assert_eq!(
+            outcomes.get(Degree::CriticalFailure),
+            Some("Your ally takes a <a href=\"/condition/clumsy\">-1 penalty</a>.")
+        );
+        assert!(Outcomes::parse("<p>Attempt a check.</p>").is_empty());
+    }
+}
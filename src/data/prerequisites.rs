@@ -0,0 +1,204 @@
+use super::{
+    ability_scores::AbilityScore, class_features::ClassFeature, feats::Feat, grants::Reference, proficiency::Proficiency, skills::Skill,
+    HasName, ObjectName,
+};
+use convert_case::{Case, Casing};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::{collections::HashMap, convert::TryFrom};
+
+lazy_static! {
+    static ref SKILL_PREREQUISITE: Regex = Regex::new(r"^(?i)(trained|expert|master|legendary) in (.+)$").unwrap();
+    static ref ABILITY_PREREQUISITE: Regex = Regex::new(r"^([A-Z][a-z]+) (\+?\d+)$").unwrap();
+}
+
+/// A single prerequisite of a feat. The original text is kept for display.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct Prerequisite {
+    pub text: String,
+    pub kind: PrerequisiteKind,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub enum PrerequisiteKind {
+    Skill {
+        skill: Skill,
+        rank: Proficiency,
+    },
+    /// Older prerequisites are ability scores like `Strength 14`, remastered ones are modifiers like `Strength +2`.
+    /// Both are stored as the modifier.
+    Ability {
+        ability: AbilityScore,
+        modifier: i32,
+    },
+    /// Probably another feat or a class feature, but that can only be resolved once all data is loaded.
+    Named(String),
+}
+
+impl From<String> for Prerequisite {
+    fn from(text: String) -> Self {
+        let text = text.trim().to_string();
+        let kind = parse_skill(&text)
+            .or_else(|| parse_ability(&text))
+            .unwrap_or_else(|| PrerequisiteKind::Named(text.trim_end_matches(" class feature").to_string()));
+        Prerequisite { text, kind }
+    }
+}
+
+fn parse_skill(text: &str) -> Option<PrerequisiteKind> {
+    let captures = SKILL_PREREQUISITE.captures(text)?;
+    let rank = match captures[1].to_lowercase().as_str() {
+        "trained" => Proficiency::Trained,
+        "expert" => Proficiency::Expert,
+        "master" => Proficiency::Master,
+        _ => Proficiency::Legendary,
+    };
+    let skill_name = (&captures[2]).to_case(Case::Title);
+    let skill = match skill_name.strip_suffix(" Lore") {
+        Some(topic) => Skill::Lore(topic.to_string()),
+        None => Skill::try_from(skill_name.as_str()).ok()?,
+    };
+    Some(PrerequisiteKind::Skill { skill, rank })
+}
+
+fn parse_ability(text: &str) -> Option<PrerequisiteKind> {
+    let captures = ABILITY_PREREQUISITE.captures(text)?;
+    let ability = match &captures[1] {
+        "Strength" => AbilityScore::Strength,
+        "Dexterity" => AbilityScore::Dexterity,
+        "Constitution" => AbilityScore::Constitution,
+        "Intelligence" => AbilityScore::Intelligence,
+        "Wisdom" => AbilityScore::Wisdom,
+        "Charisma" => AbilityScore::Charisma,
+        _ => return None,
+    };
+    let modifier = match captures[2].strip_prefix('+') {
+        Some(modifier) => modifier.parse().ok()?,
+        None => (captures[2].parse::<i32>().ok()? - 10).div_euclid(2),
+    };
+    Some(PrerequisiteKind::Ability { ability, modifier })
+}
+
+/// Resolves named prerequisites to feats and class features and remembers which feats require which.
+#[derive(Debug, Default)]
+pub struct PrerequisiteGraph {
+    feats: HashMap<String, String>,
+    class_features: HashMap<String, String>,
+    required_by: HashMap<String, Vec<String>>,
+}
+
+impl PrerequisiteGraph {
+    pub fn new(feats: &[Feat], class_features: &[ClassFeature]) -> Self {
+        let mut graph = PrerequisiteGraph {
+            feats: feats.iter().map(|f| (f.url_name(), f.name.clone())).collect(),
+            class_features: class_features.iter().map(|f| (f.url_name(), f.name.clone())).collect(),
+            required_by: HashMap::new(),
+        };
+        for feat in feats {
+            for required in graph.required_feats(feat) {
+                graph.required_by.entry(required.url_name()).or_default().push(feat.name.clone());
+            }
+        }
+        graph
+    }
+
+    pub fn resolve(&self, prerequisite: &Prerequisite) -> Option<Reference> {
+        let name = match &prerequisite.kind {
+            PrerequisiteKind::Named(name) => ObjectName(name).url_name(),
+            _ => return None,
+        };
+        let reference = |category, name: &String| Reference {
+            category,
+            name: name.clone(),
+        };
+        self.feats
+            .get(&name)
+            .map(|n| reference("feat", n))
+            .or_else(|| self.class_features.get(&name).map(|n| reference("classfeature", n)))
+    }
+
+    fn required_feat(&self, prerequisite: &Prerequisite) -> Option<Reference> {
+        self.resolve(prerequisite).filter(|r| r.category == "feat")
+    }
+
+    /// The feats among the prerequisites of this feat.
+    pub fn required_feats(&self, feat: &Feat) -> Vec<Reference> {
+        feat.prerequisites.iter().filter_map(|p| self.required_feat(p)).collect()
+    }
+
+    /// Names of all feats that list this feat as a prerequisite.
+    pub fn required_by<T: HasName>(&self, feat: &T) -> &[String] {
+        self.required_by.get(&feat.url_name()).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::feat_type::FeatType;
+
+    fn feat(name: &str, prerequisites: &[&str]) -> Feat {
+        Feat {
+            prerequisites: prerequisites.iter().map(|p| p.to_string().into()).collect(),
+            ..crate::tests::feat(name, FeatType::Class, 1, &["fighter"])
+        }
+    }
+
+    #[test]
+    fn should_link_feat_chains() {
+        let power_attack = feat("Power Attack", &[]);
+        let furious_focus = feat("Furious Focus", &["Power Attack", "trained in Athletics"]);
+        let graph = PrerequisiteGraph::new(&[power_attack.clone(), furious_focus.clone()], &[]);
+        assert_eq!(graph.required_by(&power_attack), &[String::from("Furious Focus")]);
+        assert!(graph.required_by(&furious_focus).is_empty());
+        assert_eq!(
+            graph.required_feats(&furious_focus),
+            vec![Reference {
+                category: "feat",
+                name: String::from("Power Attack"),
+            }]
+        );
+    }
+
+    fn prerequisite(text: &str) -> PrerequisiteKind {
+        Prerequisite::from(text.to_string()).kind
+    }
+
+    #[test]
+    fn should_parse_prerequisites() {
+        assert_eq!(
+            prerequisite("trained in athletics"),
+            PrerequisiteKind::Skill {
+                skill: Skill::Athletics,
+                rank: Proficiency::Trained,
+            }
+        );
+        assert_eq!(
+            prerequisite("Expert in Warfare Lore"),
+            PrerequisiteKind::Skill {
+                skill: Skill::Lore(String::from("Warfare")),
+                rank: Proficiency::Expert,
+            }
+        );
+        assert_eq!(
+            prerequisite("Strength 14"),
+            PrerequisiteKind::Ability {
+                ability: AbilityScore::Strength,
+                modifier: 2,
+            }
+        );
+        assert_eq!(
+            prerequisite("Dexterity +2"),
+            PrerequisiteKind::Ability {
+                ability: AbilityScore::Dexterity,
+                modifier: 2,
+            }
+        );
+        assert_eq!(prerequisite("rage class feature"), PrerequisiteKind::Named(String::from("rage")));
+        assert_eq!(
+            prerequisite("trained in Arcana or Nature"),
+            PrerequisiteKind::Named(String::from("trained in Arcana or Nature"))
+        );
+    }
+}
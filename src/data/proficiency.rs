@@ -1,6 +1,6 @@
 use serde_repr::*;
 
-#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug, Eq, Clone, Copy, AsRefStr)]
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug, Eq, PartialOrd, Ord, Clone, Copy, AsRefStr)]
 #[repr(i32)]
 pub enum Proficiency {
     Untrained = 0,
@@ -10,6 +10,19 @@ pub enum Proficiency {
     Legendary = 4,
 }
 
+impl Proficiency {
+    pub fn from_rank(rank: i64) -> Option<Self> {
+        match rank {
+            0 => Some(Proficiency::Untrained),
+            1 => Some(Proficiency::Trained),
+            2 => Some(Proficiency::Expert),
+            3 => Some(Proficiency::Master),
+            4 => Some(Proficiency::Legendary),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,357 @@
+use super::{
+    class_progression::{ProficiencyChange, Statistic},
+    compendium_reference,
+    damage::Die,
+    proficiency::Proficiency,
+    OneOrMany,
+};
+use convert_case::{Case, Casing};
+use itertools::Itertools;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A Foundry rule element from `system.rules`.
+/// Only the common kinds are typed. Everything else is kept as raw JSON so new or malformed rules don’t break deserialization.
+/// Rules can only be read from Foundry’s format, so they are skipped when serializing the items that have them.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(from = "Value")]
+pub enum Rule {
+    FlatModifier {
+        selectors: Vec<String>,
+        value: Value,
+        modifier_type: Option<String>,
+    },
+    Resistance {
+        damage_types: Vec<String>,
+        value: Value,
+    },
+    Weakness {
+        damage_types: Vec<String>,
+        value: Value,
+    },
+    Immunity {
+        types: Vec<String>,
+    },
+    Strike {
+        label: String,
+        damage: Option<String>,
+    },
+    GrantItem {
+        uuid: String,
+    },
+    ChoiceSet {
+        flag: Option<String>,
+    },
+    RollOption {
+        option: String,
+        toggleable: bool,
+    },
+    ActiveEffectLike {
+        mode: String,
+        path: String,
+        value: Value,
+    },
+    Sense {
+        sense: String,
+        acuity: Option<String>,
+        range: Option<Value>,
+    },
+    BaseSpeed {
+        speed: String,
+        value: Value,
+    },
+    Unknown(Value),
+}
+
+pub trait HasRules {
+    fn rules(&self) -> &[Rule];
+}
+
+impl From<Value> for Rule {
+    fn from(raw: Value) -> Self {
+        match serde_json::from_value::<JsonRule>(raw.clone()) {
+            Ok(JsonRule::FlatModifier {
+                selector,
+                value,
+                modifier_type,
+            }) => Rule::FlatModifier {
+                selectors: selector.into(),
+                value,
+                modifier_type,
+            },
+            Ok(JsonRule::Resistance { damage_type, value }) => Rule::Resistance {
+                damage_types: damage_type.into(),
+                value,
+            },
+            Ok(JsonRule::Weakness { damage_type, value }) => Rule::Weakness {
+                damage_types: damage_type.into(),
+                value,
+            },
+            Ok(JsonRule::Immunity { immunity_type }) => Rule::Immunity {
+                types: immunity_type.into(),
+            },
+            Ok(JsonRule::Strike { label, slug, damage }) => Rule::Strike {
+                label: label.or(slug).unwrap_or_default(),
+                damage: damage.map(|d| format!("{}{} {}", d.base.dice, d.base.die, d.base.damage_type)),
+            },
+            Ok(JsonRule::GrantItem { uuid }) => Rule::GrantItem { uuid },
+            Ok(JsonRule::ChoiceSet { flag }) => Rule::ChoiceSet { flag },
+            Ok(JsonRule::RollOption { option, toggleable }) => Rule::RollOption { option, toggleable },
+            Ok(JsonRule::ActiveEffectLike { mode, path, value }) => Rule::ActiveEffectLike { mode, path, value },
+            Ok(JsonRule::Sense { selector, acuity, range }) => Rule::Sense {
+                sense: selector,
+                acuity,
+                range,
+            },
+            Ok(JsonRule::BaseSpeed { selector, value }) => Rule::BaseSpeed { speed: selector, value },
+            Err(_) => Rule::Unknown(raw),
+        }
+    }
+}
+
+impl Rule {
+    pub fn proficiency_change(&self) -> Option<ProficiencyChange> {
+        match self {
+            Rule::ActiveEffectLike { mode, path, value } if mode == "upgrade" || mode == "override" => {
+                // Values can also be formulas like `ternary(gte(@actor.level,13),3,2)`, but we only handle constants.
+                let rank = match value {
+                    Value::Number(n) => n.as_i64(),
+                    Value::String(s) => s.parse().ok(),
+                    _ => None,
+                }
+                .and_then(Proficiency::from_rank)?;
+                Some(ProficiencyChange {
+                    statistic: Statistic::from_rule_path(path)?,
+                    rank,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The compendium and name of the item granted by a `GrantItem` rule.
+    /// Grants that depend on an earlier choice (e.g. `{item|flags.pf2e.rulesSelections.deity}`) can’t be resolved statically.
+    pub fn granted_item(&self) -> Option<(&str, &str)> {
+        match self {
+            Rule::GrantItem { uuid } => compendium_reference(uuid),
+            _ => None,
+        }
+    }
+
+    /// A short, human-readable description of what the rule does, if we know how to describe it.
+    pub fn summary(&self) -> Option<String> {
+        match self {
+            Rule::FlatModifier {
+                selectors,
+                value,
+                modifier_type,
+            } => {
+                let (amount, kind) = match constant(value) {
+                    Some(n) if n < 0 => (n.to_string(), "penalty"),
+                    Some(n) => (format!("+{}", n), "bonus"),
+                    None => (String::from("varying"), "modifier"),
+                };
+                Some(format!(
+                    "{} {}{} to {}",
+                    amount,
+                    modifier_type.as_ref().map(|t| format!("{} ", t)).unwrap_or_default(),
+                    kind,
+                    selectors.iter().map(|s| kebab_to_lower(s)).join(", ")
+                ))
+            }
+            Rule::Resistance { damage_types, value } => Some(format!(
+                "Resistance {}",
+                with_amount(value, &damage_types.iter().map(|t| kebab_to_lower(t)).join(", "))
+            )),
+            Rule::Weakness { damage_types, value } => Some(format!(
+                "Weakness {}",
+                with_amount(value, &damage_types.iter().map(|t| kebab_to_lower(t)).join(", "))
+            )),
+            Rule::Immunity { types } => Some(format!("Immunity to {}", types.iter().map(|t| kebab_to_lower(t)).join(", "))),
+            Rule::Strike { label, damage } => Some(match damage {
+                Some(damage) => format!("Grants a {} Strike dealing {} damage", label, damage),
+                None => format!("Grants a {} Strike", label),
+            }),
+            Rule::ChoiceSet { flag: Some(flag) } => Some(format!("Choose a {}", flag.from_case(Case::Camel).to_case(Case::Lower))),
+            Rule::RollOption { option, toggleable: true } => Some(format!("Can be toggled on or off ({})", option)),
+            Rule::ActiveEffectLike { .. } => self
+                .proficiency_change()
+                .map(|change| format!("{} proficiency increases to {}", change.statistic.label(), change.rank.as_ref())),
+            Rule::Sense { sense, acuity, range } => Some(
+                [
+                    Some(kebab_to_lower(sense)),
+                    acuity.as_ref().filter(|a| a.as_str() != "precise").map(|a| format!("({})", a)),
+                    range.as_ref().map(|r| {
+                        constant(r)
+                            .map(|n| format!("{} feet", n))
+                            .unwrap_or_else(|| String::from("(varies)"))
+                    }),
+                ]
+                .into_iter()
+                .flatten()
+                .join(" "),
+            ),
+            Rule::BaseSpeed { speed, value } => Some(match constant(value) {
+                Some(n) => format!("{} Speed of {} feet", kebab_to_lower(speed), n),
+                None => format!("{} Speed (varies)", kebab_to_lower(speed)),
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn kebab_to_lower(s: &str) -> String {
+    s.from_case(Case::Kebab).to_case(Case::Lower)
+}
+
+/// Values can also be formulas like `max(1,floor(@actor.level/2))` or level-based brackets, which can’t be shown as a number.
+fn constant(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// `5 to fire`, or `to fire (varies)` if the amount isn’t a constant
+fn with_amount(value: &Value, types: &str) -> String {
+    match constant(value) {
+        Some(n) => format!("{} to {}", n, types),
+        None => format!("to {} (varies)", types),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "key")]
+enum JsonRule {
+    FlatModifier {
+        selector: OneOrMany,
+        value: Value,
+        #[serde(rename = "type", default)]
+        modifier_type: Option<String>,
+    },
+    Resistance {
+        #[serde(rename = "type")]
+        damage_type: OneOrMany,
+        value: Value,
+    },
+    Weakness {
+        #[serde(rename = "type")]
+        damage_type: OneOrMany,
+        value: Value,
+    },
+    Immunity {
+        #[serde(rename = "type")]
+        immunity_type: OneOrMany,
+    },
+    Strike {
+        #[serde(default)]
+        label: Option<String>,
+        #[serde(default)]
+        slug: Option<String>,
+        #[serde(default)]
+        damage: Option<JsonStrikeDamage>,
+    },
+    GrantItem {
+        uuid: String,
+    },
+    ChoiceSet {
+        #[serde(default)]
+        flag: Option<String>,
+    },
+    RollOption {
+        option: String,
+        #[serde(default)]
+        toggleable: bool,
+    },
+    ActiveEffectLike {
+        #[serde(default)]
+        mode: String,
+        path: String,
+        value: Value,
+    },
+    Sense {
+        selector: String,
+        #[serde(default)]
+        acuity: Option<String>,
+        #[serde(default)]
+        range: Option<Value>,
+    },
+    BaseSpeed {
+        selector: String,
+        value: Value,
+    },
+}
+
+#[derive(Deserialize)]
+struct JsonStrikeDamage {
+    base: JsonStrikeDamageBase,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonStrikeDamageBase {
+    damage_type: String,
+    dice: i32,
+    die: Die,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_deserialize_known_rules() {
+        let rules: Vec<Rule> = serde_json::from_str(
+            r#"[
+                { "key": "FlatModifier", "selector": "ac", "type": "circumstance", "value": 1 },
+                { "key": "FlatModifier", "selector": ["fortitude", "reflex"], "value": -2 },
+                { "key": "Resistance", "type": "fire", "value": "max(1,floor(@actor.level/2))" },
+                { "key": "Weakness", "type": "cold-iron", "value": "5" },
+                { "key": "Strike", "label": "Jaws", "category": "unarmed", "damage": { "base": { "damageType": "piercing", "dice": 1, "die": "d6" } } },
+                { "key": "ChoiceSet", "flag": "barbarianInstinct", "choices": [] },
+                { "key": "Sense", "selector": "darkvision" },
+                { "key": "ActiveEffectLike", "mode": "upgrade", "path": "system.saves.will.rank", "value": 2 }
+            ]"#,
+        )
+        .expect("Deserialization failed");
+        assert_eq!(
+            rules.iter().map(|r| r.summary().unwrap()).collect_vec(),
+            vec![
+                "+1 circumstance bonus to ac",
+                "-2 penalty to fortitude, reflex",
+                "Resistance to fire (varies)",
+                "Weakness 5 to cold iron",
+                "Grants a Jaws Strike dealing 1d6 piercing damage",
+                "Choose a barbarian instinct",
+                "darkvision",
+                "Will proficiency increases to Expert",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_parse_granted_items() {
+        let grant = |uuid: &str| Rule::GrantItem { uuid: uuid.to_string() };
+        assert_eq!(
+            grant("Compendium.pf2e.feats-srd.Assurance").granted_item(),
+            Some(("feats-srd", "Assurance"))
+        );
+        assert_eq!(
+            grant("Compendium.pf2e.spells-srd.Item.Shield").granted_item(),
+            Some(("spells-srd", "Shield"))
+        );
+        assert_eq!(grant("{item|flags.pf2e.rulesSelections.deity}").granted_item(), None);
+    }
+
+    #[test]
+    fn should_preserve_unknown_rules() {
+        let raw = r#"{ "key": "TokenLight", "value": { "dim": 20 } }"#;
+        let rule: Rule = serde_json::from_str(raw).expect("Deserialization failed");
+        assert_eq!(rule, Rule::Unknown(serde_json::from_str(raw).unwrap()));
+        assert_eq!(rule.summary(), None);
+        // Known kinds with unexpected fields shouldn’t fail either
+        let broken: Rule = serde_json::from_str(r#"{ "key": "GrantItem", "uuid": 5 }"#).expect("Deserialization failed");
+        assert!(matches!(broken, Rule::Unknown(_)));
+    }
+}
@@ -0,0 +1,341 @@
+use super::{
+    damage::EquipmentDamage,
+    equipment::{Equipment, ItemType, ItemUsage, Price},
+    HasName,
+};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+
+lazy_static! {
+    static ref EXTRA_DAMAGE_REGEX: Regex = Regex::new(r"additional (\d+d\d+(?: persistent)? [a-z]+) damage").unwrap();
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum RuneKind {
+    WeaponPotency(i32),
+    Striking(i32),
+    ArmorPotency(i32),
+    Resilient(i32),
+    WeaponProperty,
+    ArmorProperty,
+}
+
+impl RuneKind {
+    pub fn for_armor(&self) -> bool {
+        matches!(self, RuneKind::ArmorPotency(_) | RuneKind::Resilient(_) | RuneKind::ArmorProperty)
+    }
+
+    pub fn is_property(&self) -> bool {
+        matches!(self, RuneKind::WeaponProperty | RuneKind::ArmorProperty)
+    }
+}
+
+/// A rune is a regular item that can be etched onto a weapon or armor.
+#[derive(Debug, Clone, Copy)]
+pub struct Rune<'a> {
+    pub item: &'a Equipment,
+    pub kind: RuneKind,
+    pub melee_only: bool,
+}
+
+/// `Striking`, `Striking (Greater)` and `Striking (Major)` give 1, 2 and 3 extra dice.
+fn grade(name: &str) -> i32 {
+    if name.ends_with("(Major)") {
+        3
+    } else if name.ends_with("(Greater)") {
+        2
+    } else {
+        1
+    }
+}
+
+/// `Weapon Potency (+2)` gives a +2 bonus.
+fn potency(name: &str) -> Option<i32> {
+    name.split("(+").nth(1)?.trim_end_matches(')').parse().ok()
+}
+
+impl<'a> Rune<'a> {
+    /// Foundry doesn’t mark runes, so fundamental runes are recognized by name and property runes by their usage.
+    pub fn from_item(item: &'a Equipment) -> Option<Self> {
+        let base = item.without_variant();
+        let kind = match (base, item.usage) {
+            ("Weapon Potency", _) => RuneKind::WeaponPotency(potency(&item.name)?),
+            ("Armor Potency", _) => RuneKind::ArmorPotency(potency(&item.name)?),
+            ("Striking", _) => RuneKind::Striking(grade(&item.name)),
+            ("Resilient", _) => RuneKind::Resilient(grade(&item.name)),
+            (_, Some(ItemUsage::EtchedOntoAWeapon | ItemUsage::EtchedOntoMeleeWeapon)) => RuneKind::WeaponProperty,
+            (_, Some(ItemUsage::EtchedOntoArmor)) => RuneKind::ArmorProperty,
+            _ => return None,
+        };
+        Some(Rune {
+            item,
+            kind,
+            melee_only: item.usage == Some(ItemUsage::EtchedOntoMeleeWeapon),
+        })
+    }
+
+    /// Extra damage of property runes like flaming, e.g. `1d6 fire`
+    pub fn extra_damage(&self) -> Option<&'a str> {
+        EXTRA_DAMAGE_REGEX
+            .captures(&self.item.description)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str())
+    }
+
+    /// Whether the rune can be etched onto the item at all, regardless of other runes.
+    pub fn fits(&self, base: &Equipment) -> bool {
+        match base_kind(base) {
+            Some(BaseKind::Weapon) => !self.kind.for_armor() && (!self.melee_only || base.range == 0),
+            Some(BaseKind::Armor) => self.kind.for_armor(),
+            None => false,
+        }
+    }
+
+    /// Each point of potency allows one property rune.
+    pub fn property_slots(&self) -> i32 {
+        match self.kind {
+            RuneKind::WeaponPotency(p) | RuneKind::ArmorPotency(p) => p,
+            _ => 0,
+        }
+    }
+
+    /// The same property rune can only be etched once, also in different grades like `Flaming (Greater)`.
+    pub fn conflicts_with(&self, other: &Rune) -> bool {
+        self.kind.is_property() && other.kind.is_property() && self.item.without_variant() == other.item.without_variant()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum BaseKind {
+    Weapon,
+    Armor,
+}
+
+fn base_kind(item: &Equipment) -> Option<BaseKind> {
+    match item.item_type {
+        ItemType::Weapon => Some(BaseKind::Weapon),
+        ItemType::Armor if item.armor.is_some() => Some(BaseKind::Armor),
+        _ => None,
+    }
+}
+
+pub fn can_have_runes(item: &Equipment) -> bool {
+    base_kind(item).is_some()
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RuneError {
+    /// A weapon rune on armor or the other way around, or a melee rune on a ranged weapon
+    DoesNotFit(String),
+    /// Two potency or two striking runes
+    DuplicateFundamental(String),
+    /// Each point of potency allows one property rune
+    TooManyPropertyRunes { allowed: i32, used: i32 },
+    /// The same property rune twice, also in different grades
+    DuplicateProperty(String),
+}
+
+impl fmt::Display for RuneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuneError::DoesNotFit(rune) => write!(f, "{} can’t be etched onto this item", rune),
+            RuneError::DuplicateFundamental(rune) => write!(f, "Only one {} rune is allowed", rune),
+            RuneError::TooManyPropertyRunes { allowed, used } => {
+                write!(
+                    f,
+                    "{} property runes need at least a +{} potency rune (have +{})",
+                    used, used, allowed
+                )
+            }
+            RuneError::DuplicateProperty(rune) => write!(f, "{} can only be etched once", rune),
+        }
+    }
+}
+
+/// A weapon or armor with runes that are known to be a legal combination.
+#[derive(Debug)]
+pub struct RunedItem<'a> {
+    pub base: &'a Equipment,
+    pub runes: Vec<Rune<'a>>,
+}
+
+impl<'a> RunedItem<'a> {
+    pub fn new(base: &'a Equipment, runes: Vec<Rune<'a>>) -> Result<Self, Vec<RuneError>> {
+        let mut errors = Vec::new();
+        for rune in runes.iter().filter(|r| !r.fits(base)) {
+            errors.push(RuneError::DoesNotFit(rune.item.name.clone()));
+        }
+        let fundamentals = runes
+            .iter()
+            .filter_map(|r| match r.kind {
+                RuneKind::WeaponPotency(_) | RuneKind::ArmorPotency(_) => Some("potency"),
+                RuneKind::Striking(_) => Some("striking"),
+                RuneKind::Resilient(_) => Some("resilient"),
+                _ => None,
+            })
+            .counts();
+        for (rune, count) in fundamentals.into_iter().sorted() {
+            if count > 1 {
+                errors.push(RuneError::DuplicateFundamental(rune.to_string()));
+            }
+        }
+        let allowed = runes.iter().map(Rune::property_slots).sum();
+        let item = RunedItem { base, runes };
+        let properties = item.property_runes().collect_vec();
+        let used = properties.len() as i32;
+        if used > allowed {
+            errors.push(RuneError::TooManyPropertyRunes { allowed, used });
+        }
+        let duplicates = properties
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| a.conflicts_with(b))
+            .map(|(a, _)| a.item.without_variant())
+            .unique()
+            .sorted();
+        for name in duplicates {
+            errors.push(RuneError::DuplicateProperty(name.to_string()));
+        }
+        if errors.is_empty() {
+            Ok(item)
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn property_runes(&self) -> impl Iterator<Item = &Rune<'a>> {
+        self.runes.iter().filter(|r| r.kind.is_property())
+    }
+
+    pub fn potency(&self) -> i32 {
+        self.runes
+            .iter()
+            .find_map(|r| match r.kind {
+                RuneKind::WeaponPotency(p) | RuneKind::ArmorPotency(p) => Some(p),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    /// Extra weapon dice from striking or item bonus to saves from resilient
+    pub fn grade(&self) -> i32 {
+        self.runes
+            .iter()
+            .find_map(|r| match r.kind {
+                RuneKind::Striking(g) | RuneKind::Resilient(g) => Some(g),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    pub fn level(&self) -> i32 {
+        self.runes.iter().map(|r| r.item.level).fold(self.base.level, i32::max)
+    }
+
+    pub fn price(&self) -> Price {
+        self.base.price + self.runes.iter().map(|r| r.item.price).sum()
+    }
+
+    pub fn damage(&self) -> Option<EquipmentDamage> {
+        self.base.damage.clone().map(|d| EquipmentDamage {
+            number_of_dice: d.number_of_dice + self.grade(),
+            ..d
+        })
+    }
+
+    /// The name as written in the books, e.g. `+1 Striking Flaming Longsword`
+    pub fn name(&self) -> String {
+        let fundamental = match (self.grade(), base_kind(self.base)) {
+            (0, _) => "",
+            (1, Some(BaseKind::Armor)) => "Resilient ",
+            (2, Some(BaseKind::Armor)) => "Greater Resilient ",
+            (_, Some(BaseKind::Armor)) => "Major Resilient ",
+            (1, _) => "Striking ",
+            (2, _) => "Greater Striking ",
+            (_, _) => "Major Striking ",
+        };
+        let potency = match self.potency() {
+            0 => String::new(),
+            p => format!("+{} ", p),
+        };
+        let properties = self.property_runes().map(|r| format!("{} ", r.item.name)).join("");
+        format!("{}{}{}{}", potency, fundamental, properties, self.base.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::equipment;
+    use serde_json::json;
+
+    fn rune(name: &str, level: i32, gp: u32, usage: &str) -> Equipment {
+        equipment(name, "equipment", level, gp, "common", &[], json!({ "usage": { "value": usage } }))
+    }
+
+    #[test]
+    fn should_recognize_runes() {
+        let potency = rune("Weapon Potency (+2)", 10, 935, "etched-onto-a-weapon");
+        let striking = rune("Striking (Greater)", 12, 1065, "etched-onto-a-weapon");
+        let flaming = rune("Flaming", 8, 500, "etched-onto-a-weapon");
+        let fortification = rune("Fortification", 12, 2000, "etched-onto-armor");
+        let rope = equipment("Rope", "equipment", 0, 0, "common", &[], json!({}));
+        let kind = |i: &Equipment| Rune::from_item(i).map(|r| r.kind);
+        assert_eq!(kind(&potency), Some(RuneKind::WeaponPotency(2)));
+        assert_eq!(kind(&striking), Some(RuneKind::Striking(2)));
+        assert_eq!(kind(&flaming), Some(RuneKind::WeaponProperty));
+        assert_eq!(kind(&fortification), Some(RuneKind::ArmorProperty));
+        assert_eq!(kind(&rope), None);
+    }
+
+    #[test]
+    fn should_combine_runes_on_weapon() {
+        let damage = json!({ "damage": { "damageType": "slashing", "dice": 1, "die": "d8" } });
+        let longsword = equipment("Longsword", "weapon", 0, 1, "common", &[], damage);
+        let potency = rune("Weapon Potency (+1)", 2, 35, "etched-onto-a-weapon");
+        let striking = rune("Striking", 4, 65, "etched-onto-a-weapon");
+        let flaming = rune("Flaming", 8, 500, "etched-onto-a-weapon");
+        let runes = [&potency, &striking, &flaming]
+            .iter()
+            .filter_map(|i| Rune::from_item(i))
+            .collect_vec();
+        let runed = RunedItem::new(&longsword, runes).expect("Valid runes");
+        assert_eq!(runed.name(), "+1 Striking Flaming Longsword");
+        assert_eq!(runed.level(), 8);
+        assert_eq!(runed.price(), Price::from_copper(60_100));
+        assert_eq!(runed.damage().map(|d| d.to_string()), Some(String::from("2d8 Slashing")));
+        let flaming = Equipment {
+            description: String::from("<p>The weapon deals an additional 1d6 fire damage on a successful Strike.</p>"),
+            ..rune("Flaming", 8, 500, "etched-onto-a-weapon")
+        };
+        assert_eq!(Rune::from_item(&flaming).and_then(|r| r.extra_damage()), Some("1d6 fire"));
+    }
+
+    #[test]
+    fn should_reject_invalid_combinations() {
+        let longbow = equipment("Longbow", "weapon", 0, 0, "common", &[], json!({ "range": 100 }));
+        let potency = rune("Weapon Potency (+1)", 2, 35, "etched-onto-a-weapon");
+        let flaming = rune("Flaming", 8, 500, "etched-onto-a-weapon");
+        let flaming_greater = rune("Flaming (Greater)", 15, 6000, "etched-onto-a-weapon");
+        let vorpal = rune("Vorpal", 17, 15000, "etched-onto-melee-weapon");
+        let fortification = rune("Fortification", 12, 2000, "etched-onto-armor");
+        let runes = [&potency, &flaming, &flaming_greater, &vorpal, &fortification]
+            .iter()
+            .filter_map(|i| Rune::from_item(i))
+            .collect_vec();
+        let errors = RunedItem::new(&longbow, runes).expect_err("Invalid runes");
+        assert_eq!(
+            errors,
+            vec![
+                RuneError::DoesNotFit(String::from("Vorpal")),
+                RuneError::DoesNotFit(String::from("Fortification")),
+                RuneError::TooManyPropertyRunes { allowed: 1, used: 4 },
+                RuneError::DuplicateProperty(String::from("Flaming")),
+            ]
+        );
+    }
+}
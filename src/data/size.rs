@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, AsRefStr)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, AsRefStr, IntoStaticStr, EnumIter)]
 pub enum Size {
     #[serde(rename = "tiny")]
     Tiny,
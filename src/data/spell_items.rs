@@ -0,0 +1,197 @@
+use super::{
+    equipment::{ItemType, Price},
+    spells::{Spell, SpellCategory},
+    HasName, ObjectName,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::ops::Range;
+
+lazy_static! {
+    static ref STAFF_RANK_REGEX: Regex =
+        Regex::new(r"<li>\s*<strong>\s*(Cantrips?|\d+(?:st|nd|rd|th))\s*</strong>:?\s*(.*?)\s*</li>").unwrap();
+    static ref SPELL_LINK_REGEX: Regex = Regex::new(r#"<a href="([^"]+)">(.*?)</a>"#).unwrap();
+    static ref TAG_REGEX: Regex = Regex::new("<[^>]+>").unwrap();
+}
+
+/// Scrolls and wands exist for every spell, so Foundry only has generic items for them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, AsRefStr, EnumIter)]
+pub enum SpellItemKind {
+    Scroll,
+    Wand,
+}
+
+impl SpellItemKind {
+    /// Item level and price by spell rank from the scroll and magic wand tables (Core Rulebook p. 564 and 568)
+    pub fn level_and_price(&self, rank: i32) -> Option<(i32, Price)> {
+        let (level, gp) = match (self, rank) {
+            (SpellItemKind::Scroll, 1) => (1, 4),
+            (SpellItemKind::Scroll, 2) => (3, 12),
+            (SpellItemKind::Scroll, 3) => (5, 30),
+            (SpellItemKind::Scroll, 4) => (7, 70),
+            (SpellItemKind::Scroll, 5) => (9, 150),
+            (SpellItemKind::Scroll, 6) => (11, 300),
+            (SpellItemKind::Scroll, 7) => (13, 600),
+            (SpellItemKind::Scroll, 8) => (15, 1300),
+            (SpellItemKind::Scroll, 9) => (17, 3000),
+            (SpellItemKind::Scroll, 10) => (19, 8000),
+            (SpellItemKind::Wand, 1) => (3, 60),
+            (SpellItemKind::Wand, 2) => (5, 160),
+            (SpellItemKind::Wand, 3) => (7, 360),
+            (SpellItemKind::Wand, 4) => (9, 700),
+            (SpellItemKind::Wand, 5) => (11, 1500),
+            (SpellItemKind::Wand, 6) => (13, 3250),
+            (SpellItemKind::Wand, 7) => (15, 7500),
+            (SpellItemKind::Wand, 8) => (17, 19000),
+            (SpellItemKind::Wand, 9) => (19, 40000),
+            _ => return None,
+        };
+        Some((level, Price::from_copper(gp * 100)))
+    }
+
+    /// Scrolls and wands can hold the spell at its own rank or heightened to any higher rank.
+    pub fn ranks(&self, spell: &Spell) -> Vec<(i32, i32, Price)> {
+        if spell.category != SpellCategory::Spell || spell.is_cantrip() {
+            return Vec::new();
+        }
+        (spell.level..=10)
+            .filter_map(|rank| self.level_and_price(rank).map(|(level, price)| (rank, level, price)))
+            .collect()
+    }
+
+    /// The same category as regular items of that type, so wands aren’t listed with the consumables
+    pub fn category(&self) -> &'static str {
+        match self {
+            SpellItemKind::Scroll => ItemType::Consumable.into(),
+            SpellItemKind::Wand => ItemType::Equipment.into(),
+        }
+    }
+
+    pub fn item_name(&self, spell: &Spell) -> String {
+        format!("{} of {}", self.as_ref(), spell.name)
+    }
+
+    pub fn url_name(&self, spell: &Spell) -> String {
+        ObjectName(&self.item_name(spell)).url_name()
+    }
+}
+
+pub fn rank_as_string(rank: i32) -> String {
+    let suffix = match (rank % 10, rank % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", rank, suffix)
+}
+
+/// A spell a staff can cast, linked to its spell page.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StaffSpell {
+    pub name: String,
+    pub url: String,
+}
+
+/// The spells of a staff by rank (0 for cantrips) and where its list is in the description.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct StaffSpells {
+    pub ranks: Vec<(i32, Vec<StaffSpell>)>,
+    pub list: Range<usize>,
+}
+
+impl StaffSpells {
+    /// Staves list their spells as `<li><strong>1st</strong> <a href="/spell/...">...</a>, ...</li>`,
+    /// but not every spell is linked in the data, so unlinked names get a link by name.
+    pub fn parse(description: &str) -> Option<Self> {
+        let mut spells = StaffSpells::default();
+        for captures in STAFF_RANK_REGEX.captures_iter(description) {
+            let rank = captures[1].trim_end_matches(char::is_alphabetic).parse().unwrap_or(0);
+            let entries = captures[2]
+                .split(", ")
+                .filter_map(|entry| {
+                    let (name, url) = match SPELL_LINK_REGEX.captures(entry) {
+                        Some(link) => (link[2].to_string(), link[1].to_string()),
+                        None => {
+                            let name = TAG_REGEX.replace_all(entry, "").trim().to_string();
+                            let url = format!("/spell/{}", ObjectName(&name).url_name());
+                            (name, url)
+                        }
+                    };
+                    (!name.is_empty()).then_some(StaffSpell { name, url })
+                })
+                .collect();
+            let range = captures.get(0).unwrap().range();
+            if spells.ranks.is_empty() {
+                spells.list = range;
+            } else {
+                spells.list.end = range.end;
+            }
+            spells.ranks.push((rank, entries));
+        }
+        if spells.ranks.is_empty() {
+            return None;
+        }
+        // Include the surrounding list so it can be replaced as a whole
+        let before = &description[..spells.list.start];
+        if let Some(start) = before.rfind("<ul>").filter(|&start| before[start + 4..].trim().is_empty()) {
+            spells.list.start = start;
+        }
+        let after = &description[spells.list.end..];
+        if let Some(end) = after.find("</ul>").filter(|&end| after[..end].trim().is_empty()) {
+            spells.list.end += end + 5;
+        }
+        Some(spells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_price_scrolls_and_wands() {
+        assert_eq!(SpellItemKind::Scroll.level_and_price(3), Some((5, Price::from_copper(3000))));
+        assert_eq!(SpellItemKind::Wand.level_and_price(1).map(|(l, _)| l), Some(3));
+        assert_eq!(SpellItemKind::Wand.level_and_price(10), None);
+        assert_eq!(SpellItemKind::Scroll.category(), "Consumable");
+        assert_eq!(SpellItemKind::Wand.category(), "Equipment");
+        assert_eq!(rank_as_string(3), "3rd");
+        assert_eq!(rank_as_string(11), "11th");
+    }
+
+    #[test]
+    fn should_parse_staff_spells() {
+        let description = "<p>This staff is made of ash.</p>\n<ul>\n<li><strong>Cantrip</strong> <a href=\"/spell/light\">light</a></li>\n<li><strong>1st</strong> <a href=\"/spell/burning_hands\">burning hands</a>, <em>fear</em></li>\n</ul>\n<p>Craft Requirements</p>";
+        let spells = StaffSpells::parse(description).expect("Staff spells");
+        assert_eq!(
+            spells.ranks,
+            vec![
+                (
+                    0,
+                    vec![StaffSpell {
+                        name: String::from("light"),
+                        url: String::from("/spell/light")
+                    }]
+                ),
+                (
+                    1,
+                    vec![
+                        StaffSpell {
+                            name: String::from("burning hands"),
+                            url: String::from("/spell/burning_hands")
+                        },
+                        StaffSpell {
+                            name: String::from("fear"),
+                            url: String::from("/spell/fear")
+                        },
+                    ]
+                ),
+            ]
+        );
+        assert!(description[spells.list.clone()].starts_with("<ul>"));
+        assert!(description[spells.list].ends_with("</ul>"));
+        assert_eq!(StaffSpells::parse("<p>A plain stick.</p>"), None);
+    }
+}
@@ -1,5 +1,6 @@
 use super::{
     equipment::StringOrNum,
+    outcomes::Outcomes,
     traits::{JsonTraits, Traits},
     HasLevel, HasName, ValueWrapper, URL_REMOVE_CHARACTERS, URL_REPLACE_CHARACTERS,
 };
@@ -20,6 +21,7 @@ pub struct Spell {
     // pub damage: SpellDamage,
     // pub damage_type: DamageType,
     pub description: String,
+    pub outcomes: Outcomes,
     pub duration: String,
     pub level: i32,
     pub range: String,
@@ -77,6 +79,7 @@ impl From<JsonSpell> for Spell {
             _ => None,
         };
 
+        let description = text_cleanup(&js.system.description.value);
         Spell {
             name: js.name.clone(),
             basic_save,
@@ -98,7 +101,8 @@ impl From<JsonSpell> for Spell {
             category: js.system.category.value,
             // damage: js.data.damage,
             // damage_type: js.data.damage_type.value,
-            description: text_cleanup(&js.system.description.value),
+            outcomes: Outcomes::parse(&description),
+            description,
             duration: js.system.duration.value,
             level: js.system.level.value,
             range: js.system.range.value,
@@ -2,12 +2,15 @@ use super::creature::Alignment;
 use super::equipment::ItemUsage;
 use super::size::Size;
 use super::ValueWrapper;
-use crate::html::{write_full_html_document, HtmlPage};
+use super::{HasName, ObjectName};
+use crate::html::{inline_rarity_if_not_common, write_full_html_document, HtmlPage};
+use convert_case::{Case, Casing};
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::{self, Write};
 use std::{fs, io, io::BufReader};
 
@@ -58,7 +61,7 @@ impl From<JsonTraits> for Traits {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq, AsRefStr)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq, AsRefStr, IntoStaticStr, EnumIter)]
 #[serde(rename_all = "lowercase")]
 pub enum Rarity {
     #[serde(alias = "Common")]
@@ -127,26 +130,200 @@ impl Translations {
     }
 }
 
+/// The url name of a trait page. Parameterized traits like `deadly-d10` share the page of the base trait.
+pub fn trait_url_name(name: &str) -> String {
+    trait_display_name(name).to_lowercase()
+}
+
+fn trait_display_name(name: &str) -> String {
+    clean_trait_name(name).to_case(Case::Pascal)
+}
+
+/// What kind of thing a trait applies to. Foundry only classifies traits in the system’s code, not in the data,
+/// so this is derived from where the trait is used. A trait used on several kinds of things belongs to the first
+/// of them in this order, e.g. `fire` is a spell trait even though creatures and items have it too.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, AsRefStr)]
+pub enum TraitKind {
+    Ancestry,
+    Class,
+    Weapon,
+    Armor,
+    Spell,
+    Creature,
+    Action,
+    Equipment,
+    Feat,
+    Other,
+}
+
+impl TraitKind {
+    fn title(&self) -> String {
+        format!("{} Traits", self.as_ref())
+    }
+
+    fn url_name(&self) -> String {
+        format!("{}_traits", self.as_ref().to_lowercase())
+    }
+}
+
+struct TraitEntry {
+    category: &'static str,
+    name: String,
+    level: Option<i32>,
+    rarity: Rarity,
+}
+
+/// Everything that has a given trait, in the order the categories were added.
+#[derive(Default)]
+pub struct TraitIndex {
+    categories: Vec<(&'static str, &'static str)>,
+    names: BTreeMap<String, String>,
+    entries: HashMap<String, Vec<TraitEntry>>,
+    kinds: HashMap<String, BTreeSet<TraitKind>>,
+    // Traits named after a class or ancestry only belong to that kind, no matter where else they are used.
+    named: HashMap<String, TraitKind>,
+}
+
+impl TraitIndex {
+    /// `details` returns the kind of trait this element has, its traits and its level, or `None` if it should be skipped.
+    pub fn add<T: HasName>(
+        &mut self,
+        category: &'static str,
+        title: &'static str,
+        elements: &[(T, HtmlPage)],
+        details: impl Fn(&T) -> Option<(TraitKind, &Traits, Option<i32>)>,
+    ) {
+        self.categories.push((category, title));
+        for (e, (kind, traits, level)) in elements.iter().filter_map(|(e, _)| Some((e, details(e)?))) {
+            let rarity = traits.rarity.as_ref().to_lowercase();
+            for t in traits.misc.iter().filter(|t| t != &&rarity).unique_by(|t| trait_url_name(t)) {
+                let url_name = trait_url_name(t);
+                self.names.entry(url_name.clone()).or_insert_with(|| trait_display_name(t));
+                self.kinds.entry(url_name.clone()).or_default().insert(kind);
+                self.entries.entry(url_name).or_default().push(TraitEntry {
+                    category,
+                    name: e.name().to_string(),
+                    level,
+                    rarity: traits.rarity,
+                });
+            }
+        }
+    }
+
+    /// Marks the traits with these names (e.g. all classes) as belonging to exactly this kind.
+    pub fn add_named(&mut self, kind: TraitKind, names: &[String]) {
+        self.named.extend(names.iter().map(|n| (trait_url_name(n), kind)));
+    }
+
+    fn kind(&self, url_name: &str) -> TraitKind {
+        if let Some(kind) = self.named.get(url_name) {
+            return *kind;
+        }
+        self.kinds
+            .get(url_name)
+            .and_then(|kinds| kinds.iter().next())
+            .copied()
+            .unwrap_or(TraitKind::Other)
+    }
+
+    fn render_entries(&self, page: &mut String, url_name: &str) {
+        let entries = match self.entries.get(url_name) {
+            Some(entries) => entries,
+            None => return,
+        };
+        for (category, group) in &entries.iter().group_by(|e| e.category) {
+            let title = self
+                .categories
+                .iter()
+                .find(|(c, _)| c == &category)
+                .map(|(_, t)| *t)
+                .unwrap_or(category);
+            write!(page, "<h2>{}</h2>", title);
+            page.push_str("<table class=\"overview\"><thead><tr><td>Name</td><td>Level</td></tr></thead>");
+            for entry in group {
+                write!(
+                    page,
+                    "<tr><td><a href=\"/{}/{}\">{}</a>{}</td><td>{}</td></tr>",
+                    category,
+                    ObjectName(&entry.name).url_name(),
+                    entry.name,
+                    inline_rarity_if_not_common(&entry.rarity),
+                    entry.level.map(|l| l.to_string()).unwrap_or_default(),
+                );
+            }
+            page.push_str("</table>");
+        }
+    }
+}
+
+fn add_trait_header(page: &mut String, kinds: &BTreeMap<TraitKind, Vec<(String, &str)>>) {
+    page.push_str(r#"<div class="header">"#);
+    page.push_str(r#"<span><a href="/trait/"><div>All</div></a></span>"#);
+    for kind in kinds.keys() {
+        write!(
+            page,
+            r#"<span><a href="/trait/{}"><div>{}</div></a></span>"#,
+            kind.url_name(),
+            kind.as_ref()
+        );
+    }
+    page.push_str("</div>");
+}
+
+fn render_trait_grid(page: &mut String, traits: &[(String, &str)]) {
+    page.push_str("<div class=\"gridlist\">");
+    for (url_name, name) in traits {
+        writeln!(page, "<span><a href=\"/trait/{}\">{}</a></span>", url_name, name);
+    }
+    page.push_str("</div>");
+}
+
 // These work differently from the other data structures because they’re not deserialized from a
 // folder of JSONs.
-pub(crate) fn render_traits(output_path: &str, translations: &Translations) -> io::Result<Vec<HtmlPage>> {
+pub(crate) fn render_traits(output_path: &str, translations: &Translations, index: &TraitIndex) -> io::Result<Vec<HtmlPage>> {
     fs::create_dir_all(output_path)?;
-    let mut list = String::with_capacity(100_000);
-    list.push_str("<div id=\"gridlist\">");
     let mut pages = Vec::new();
+    // Some traits are used in the data but have no description
+    let mut traits: BTreeMap<String, (&str, Option<&str>)> =
+        index.names.iter().map(|(url, name)| (url.clone(), (name.as_str(), None))).collect();
     for (key, val) in &translations.traits {
-        let trait_name = key.to_lowercase();
+        traits.insert(key.to_lowercase(), (key, Some(val)));
+    }
+    let mut by_kind: BTreeMap<TraitKind, Vec<(String, &str)>> = BTreeMap::new();
+    for (trait_name, (key, val)) in traits {
+        let kind = index.kind(&trait_name);
+        // The search index only gets the description, not the (potentially very long) list of entries.
         let page = HtmlPage {
             name: key.to_string(),
-            content: format!("<h1><a href=\"/trait/{}\">{}</a></h1><hr/>{}", trait_name, key, val),
+            content: format!(
+                "<h1><a href=\"/trait/{}\">{}</a><span class=\"type\">{}</span></h1><hr/>{}",
+                trait_name,
+                key,
+                kind.title(),
+                val.unwrap_or_default()
+            ),
             category: String::from("trait"),
             id: format!("trait-{}", key),
+            ..HtmlPage::default()
         };
-        write_full_html_document(&format!("{}/{}", output_path, trait_name), &page.name, &page.content)?;
-        writeln!(list, "<span><a href=\"{}\">{}</a></span>", trait_name, key);
+        let mut content = page.content.clone();
+        index.render_entries(&mut content, &trait_name);
+        write_full_html_document(&format!("{}/{}", output_path, trait_name), &page.name, &content)?;
+        by_kind.entry(kind).or_default().push((trait_name.clone(), key));
         pages.push(page);
     }
-    list.push_str("</div>");
+    let mut list = String::with_capacity(100_000);
+    add_trait_header(&mut list, &by_kind);
+    for (kind, traits) in &by_kind {
+        write!(list, "<h2><a href=\"/trait/{}\">{}</a></h2>", kind.url_name(), kind.title());
+        render_trait_grid(&mut list, traits);
+
+        let mut kind_page = String::with_capacity(20_000);
+        add_trait_header(&mut kind_page, &by_kind);
+        write!(kind_page, "<h1>{}</h1><hr/>", kind.title());
+        render_trait_grid(&mut kind_page, traits);
+        write_full_html_document(&format!("{}/{}", output_path, kind.url_name()), &kind.title(), &kind_page)?;
+    }
     list.push_str("<div style=\"height: 2em\"></div>");
     list.push_str("<a href=\"/\">Back</a>");
     write_full_html_document(&format!("{}/index.html", output_path), "Traits", &list)?;
@@ -187,6 +364,80 @@ mod test {
         assert_eq!(None, TRANSLATIONS.traits.get("FatalD8"));
     }
 
+    #[test]
+    fn should_group_trait_entries_by_category() {
+        struct Named(&'static str, Traits);
+        impl HasName for Named {
+            fn name(&self) -> &str {
+                self.0
+            }
+        }
+        let traits = |misc: &[&str], rarity| Traits {
+            misc: misc.iter().map(|t| t.to_string()).collect(),
+            rarity,
+            alignment: None,
+            size: None,
+        };
+        let weapons = [
+            (Named("Greatpick", traits(&["fatal-d12"], Rarity::Common)), HtmlPage::default()),
+            (
+                Named("Aklys", traits(&["uncommon", "ranged-trip", "thrown-20"], Rarity::Uncommon)),
+                HtmlPage::default(),
+            ),
+        ];
+        let creatures = [(
+            Named("Kobold Warrior", traits(&["humanoid", "kobold"], Rarity::Common)),
+            HtmlPage::default(),
+        )];
+        let mut index = TraitIndex::default();
+        index.add("item", "Items", &weapons, |w| Some((TraitKind::Weapon, &w.1, Some(0))));
+        index.add("creature", "Creatures", &creatures, |c| Some((TraitKind::Creature, &c.1, Some(-1))));
+
+        assert_eq!(trait_url_name("fatal-d12"), "fatal");
+        assert_eq!(trait_url_name("half-elf"), "halfelf");
+        assert!(!index.entries.contains_key("uncommon"));
+        let mut page = String::new();
+        index.render_entries(&mut page, "thrown");
+        assert!(page.starts_with("<h2>Items</h2>"));
+        assert!(page.contains("<a href=\"/item/aklys\">Aklys</a>"));
+        assert!(!page.contains("Creatures"));
+        let mut page = String::new();
+        index.render_entries(&mut page, "kobold");
+        assert!(page.contains("<h2>Creatures</h2>"));
+        assert!(page.contains("<td>-1</td>"));
+    }
+
+    #[test]
+    fn should_classify_traits_by_usage() {
+        struct Tagged(TraitKind, Traits);
+        impl HasName for Tagged {
+            fn name(&self) -> &str {
+                "Test"
+            }
+        }
+        let traits = |misc: &[&str]| Traits {
+            misc: misc.iter().map(|t| t.to_string()).collect(),
+            rarity: Rarity::Common,
+            alignment: None,
+            size: None,
+        };
+        let elements = [
+            (Tagged(TraitKind::Weapon, traits(&["deadly-d10", "kobold"])), HtmlPage::default()),
+            (Tagged(TraitKind::Creature, traits(&["kobold", "fire"])), HtmlPage::default()),
+            (Tagged(TraitKind::Spell, traits(&["fire"])), HtmlPage::default()),
+            (Tagged(TraitKind::Feat, traits(&["concentrate"])), HtmlPage::default()),
+            (Tagged(TraitKind::Action, traits(&["concentrate"])), HtmlPage::default()),
+        ];
+        let mut index = TraitIndex::default();
+        index.add_named(TraitKind::Ancestry, &[String::from("Kobold")]);
+        index.add("test", "Tests", &elements, |t| Some((t.0, &t.1, None)));
+        assert_eq!(index.kind("deadly"), TraitKind::Weapon);
+        assert_eq!(index.kind("kobold"), TraitKind::Ancestry);
+        assert_eq!(index.kind("fire"), TraitKind::Spell);
+        assert_eq!(index.kind("concentrate"), TraitKind::Action);
+        assert_eq!(index.kind("unused"), TraitKind::Other);
+    }
+
     #[test]
     fn test_localization() {
         assert_eq!(TRANSLATIONS.get_by_key("PF2E.NPC.Abilities.Glossary.Telepathy"), Some("<p>A monster with telepathy can communicate mentally with any creatures within the listed radius, as long as they share a language. This doesn't give any special access to their thoughts, and communicates no more information than normal speech would.</p>"));
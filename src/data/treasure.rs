@@ -0,0 +1,223 @@
+use super::{
+    equipment::{Equipment, ItemType, Price},
+    traits::Rarity,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// One row of the party treasure by level table (Core Rulebook p. 509), balanced for four characters.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct TreasureRow {
+    pub level: i32,
+    /// Item level and how many items of that level
+    pub permanent_items: &'static [(i32, usize)],
+    pub consumables: &'static [(i32, usize)],
+    /// In gp
+    pub currency: u32,
+    pub currency_per_additional_pc: u32,
+}
+
+macro_rules! treasure_row {
+    ($level: literal, [$($p: expr),*], [$($c: expr),*], $currency: literal, $additional: literal) => {
+        TreasureRow {
+            level: $level,
+            permanent_items: &[$($p),*],
+            consumables: &[$($c),*],
+            currency: $currency,
+            currency_per_additional_pc: $additional,
+        }
+    };
+}
+
+pub const TREASURE_BY_LEVEL: [TreasureRow; 20] = [
+    treasure_row!(1, [(2, 2), (1, 2)], [(2, 2), (1, 3)], 40, 10),
+    treasure_row!(2, [(3, 2), (2, 2)], [(3, 2), (2, 2), (1, 2)], 70, 18),
+    treasure_row!(3, [(4, 2), (3, 2)], [(4, 2), (3, 2), (2, 2)], 120, 30),
+    treasure_row!(4, [(5, 2), (4, 2)], [(5, 2), (4, 2), (3, 2)], 200, 50),
+    treasure_row!(5, [(6, 2), (5, 2)], [(6, 2), (5, 2), (4, 2)], 320, 80),
+    treasure_row!(6, [(7, 2), (6, 2)], [(7, 2), (6, 2), (5, 2)], 500, 125),
+    treasure_row!(7, [(8, 2), (7, 2)], [(8, 2), (7, 2), (6, 2)], 720, 180),
+    treasure_row!(8, [(9, 2), (8, 2)], [(9, 2), (8, 2), (7, 2)], 1000, 250),
+    treasure_row!(9, [(10, 2), (9, 2)], [(10, 2), (9, 2), (8, 2)], 1400, 350),
+    treasure_row!(10, [(11, 2), (10, 2)], [(11, 2), (10, 2), (9, 2)], 2000, 500),
+    treasure_row!(11, [(12, 2), (11, 2)], [(12, 2), (11, 2), (10, 2)], 2800, 700),
+    treasure_row!(12, [(13, 2), (12, 2)], [(13, 2), (12, 2), (11, 2)], 4000, 1000),
+    treasure_row!(13, [(14, 2), (13, 2)], [(14, 2), (13, 2), (12, 2)], 6000, 1500),
+    treasure_row!(14, [(15, 2), (14, 2)], [(15, 2), (14, 2), (13, 2)], 9000, 2250),
+    treasure_row!(15, [(16, 2), (15, 2)], [(16, 2), (15, 2), (14, 2)], 13000, 3250),
+    treasure_row!(16, [(17, 2), (16, 2)], [(17, 2), (16, 2), (15, 2)], 20000, 5000),
+    treasure_row!(17, [(18, 2), (17, 2)], [(18, 2), (17, 2), (16, 2)], 30000, 7500),
+    treasure_row!(18, [(19, 2), (18, 2)], [(19, 2), (18, 2), (17, 2)], 48000, 12000),
+    treasure_row!(19, [(20, 2), (19, 2)], [(20, 2), (19, 2), (18, 2)], 80000, 20000),
+    treasure_row!(20, [(20, 4)], [(20, 4)], 140000, 35000),
+];
+
+pub fn treasure_row(party_level: i32) -> Option<&'static TreasureRow> {
+    TREASURE_BY_LEVEL.iter().find(|r| r.level == party_level)
+}
+
+impl TreasureRow {
+    /// The table is made for four characters, other party sizes only change the currency.
+    pub fn currency(&self, party_size: u32) -> Price {
+        let gp = match party_size {
+            0..=4 => self.currency.saturating_sub((4 - party_size) * self.currency_per_additional_pc),
+            _ => self.currency + (party_size - 4) * self.currency_per_additional_pc,
+        };
+        Price::from_copper(gp * 100)
+    }
+}
+
+/// Mulberry32, small enough to be reimplemented by the loot page so both produce the same loot for a seed.
+#[derive(Debug)]
+pub struct Rng(u32);
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.0 = self.0.wrapping_add(0x6D2B_79F5);
+        let mut t = self.0;
+        t = (t ^ (t >> 15)).wrapping_mul(t | 1);
+        t ^= t.wrapping_add((t ^ (t >> 7)).wrapping_mul(t | 61));
+        t ^ (t >> 14)
+    }
+
+    pub fn choose<'a, T>(&mut self, elements: &'a [T]) -> Option<&'a T> {
+        match elements.len() {
+            0 => None,
+            len => elements.get(self.next_u32() as usize % len),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LootKind {
+    Permanent,
+    Consumable,
+}
+
+pub fn loot_kind(item: &Equipment) -> Option<LootKind> {
+    match item.item_type {
+        ItemType::Consumable => Some(LootKind::Consumable),
+        ItemType::Weapon | ItemType::Armor | ItemType::Shield | ItemType::Equipment => Some(LootKind::Permanent),
+        _ => None,
+    }
+}
+
+/// All items that can be rolled, by kind and item level.
+#[derive(Debug, Default)]
+pub struct LootCandidates<'a> {
+    pub permanent_items: BTreeMap<i32, Vec<&'a Equipment>>,
+    pub consumables: BTreeMap<i32, Vec<&'a Equipment>>,
+}
+
+impl<'a> LootCandidates<'a> {
+    /// Uncommon and rare items are only included on request, unique items never.
+    pub fn new(items: impl IntoIterator<Item = &'a Equipment>, include_uncommon: bool) -> Self {
+        let mut candidates = LootCandidates::default();
+        for item in items {
+            let allowed = match item.traits.rarity {
+                Rarity::Common => true,
+                Rarity::Uncommon | Rarity::Rare => include_uncommon,
+                Rarity::Unique => false,
+            };
+            let list = match loot_kind(item) {
+                Some(LootKind::Permanent) => &mut candidates.permanent_items,
+                Some(LootKind::Consumable) => &mut candidates.consumables,
+                None => continue,
+            };
+            if allowed && item.level > 0 {
+                list.entry(item.level).or_default().push(item);
+            }
+        }
+        candidates
+    }
+
+    /// Rolls one item for every slot of the table row, slots without any candidates stay empty.
+    pub fn generate(&self, party_level: i32, party_size: u32, seed: u32) -> Option<Loot<'a>> {
+        let row = treasure_row(party_level)?;
+        let mut rng = Rng::new(seed);
+        let mut roll = |slots: &[(i32, usize)], candidates: &BTreeMap<i32, Vec<&'a Equipment>>| {
+            let mut items = Vec::new();
+            for &(level, count) in slots {
+                let candidates = candidates.get(&level).map(Vec::as_slice).unwrap_or_default();
+                items.extend((0..count).filter_map(|_| rng.choose(candidates).copied()));
+            }
+            items
+        };
+        Some(Loot {
+            permanent_items: roll(row.permanent_items, &self.permanent_items),
+            consumables: roll(row.consumables, &self.consumables),
+            currency: row.currency(party_size),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Loot<'a> {
+    pub permanent_items: Vec<&'a Equipment>,
+    pub consumables: Vec<&'a Equipment>,
+    pub currency: Price,
+}
+
+impl Loot<'_> {
+    pub fn total(&self) -> Price {
+        self.permanent_items.iter().chain(&self.consumables).map(|i| i.price).sum::<Price>() + self.currency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, item_type: &str, level: i32, rarity: &str) -> Equipment {
+        crate::tests::equipment(name, item_type, level, 10, rarity, &[], serde_json::json!({}))
+    }
+
+    #[test]
+    fn should_produce_known_random_numbers() {
+        // Reference values from the JavaScript implementation on the loot page
+        let mut rng = Rng::new(1);
+        assert_eq!(
+            [rng.next_u32(), rng.next_u32(), rng.next_u32()],
+            [2_693_262_067, 11_749_833, 2_265_367_787]
+        );
+    }
+
+    #[test]
+    fn should_scale_currency_with_party_size() {
+        let row = treasure_row(3).unwrap();
+        assert_eq!(row.currency(4).as_copper(), 12_000);
+        assert_eq!(row.currency(6).as_copper(), 18_000);
+        assert_eq!(row.currency(2).as_copper(), 6_000);
+        assert_eq!(treasure_row(21), None);
+        for (row, level) in TREASURE_BY_LEVEL.iter().zip(1..) {
+            assert_eq!(row.level, level);
+        }
+    }
+
+    #[test]
+    fn should_generate_loot_deterministically() {
+        let items = [
+            item("Cloak", "equipment", 2, "common"),
+            item("Boots", "equipment", 2, "uncommon"),
+            item("Ring", "equipment", 1, "common"),
+            item("Potion", "consumable", 2, "common"),
+            item("Elixir", "consumable", 1, "common"),
+            item("Gem", "treasure", 1, "common"),
+        ];
+        let candidates = LootCandidates::new(&items, false);
+        let loot = candidates.generate(1, 4, 42).unwrap();
+        assert!(loot.permanent_items.iter().all(|i| i.name != "Boots"));
+        assert_eq!(loot.permanent_items.len(), 4);
+        assert_eq!(loot.consumables.len(), 5);
+        assert_eq!(loot.total().as_copper(), 9 * 1000 + 4000);
+        let again = candidates.generate(1, 4, 42).unwrap();
+        assert_eq!(loot.permanent_items, again.permanent_items);
+        let with_uncommon = LootCandidates::new(&items, true);
+        assert_eq!(with_uncommon.permanent_items[&2].len(), 2);
+        assert!(candidates.generate(0, 4, 42).is_none());
+    }
+}
@@ -1,9 +1,19 @@
-use crate::data::{actions::Action, HasName};
-use crate::html::{render_traits, HtmlPage, Template};
-use std::{borrow::Cow, fmt::Write};
+use crate::data::{
+    action_type::ActionType,
+    actions::{block_paragraphs, Action, ActionBlock, BlockLabel},
+    creature::Npc,
+    feats::Feat,
+    grants::Grants,
+    outcomes::Outcomes,
+    spells::Spell,
+    HasName,
+};
+use crate::html::{render_grants, render_outcomes, render_traits, write_json, HtmlPage, Template};
+use serde::Serialize;
+use std::{borrow::Cow, fmt::Write, io};
 
-impl Template<()> for Action {
-    fn render(&self, _: ()) -> Cow<'_, str> {
+impl Template<&Grants> for Action {
+    fn render(&self, grants: &Grants) -> Cow<'_, str> {
         let mut page = String::with_capacity(2000);
         write!(
             page,
@@ -13,11 +23,12 @@ impl Template<()> for Action {
             self.action_type.img(&self.number_of_actions)
         );
         render_traits(&mut page, &self.traits);
-        page.push_str(&self.description);
+        render_action_description(&mut page, &render_outcomes(&self.description, &self.outcomes), &self.block);
+        render_grants(&mut page, grants, "action", self);
         Cow::Owned(page)
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], _: &Grants) -> String {
         let mut page = String::with_capacity(10_000);
         page.push_str("<div id=\"gridlist\">");
         for (action, _) in elements {
@@ -36,6 +47,128 @@ impl Template<()> for Action {
     fn category(&self) -> Cow<'_, str> {
         Cow::Borrowed("Action")
     }
+
+    fn outcomes(&self) -> Option<&Outcomes> {
+        Some(&self.outcomes)
+    }
+}
+
+/// Renders the description with its labelled paragraphs taken from the parsed block.
+/// Frequency, trigger and requirements are grouped in that order where the first of them was and followed by a line,
+/// the effect stays where it is.
+pub(crate) fn render_action_description(page: &mut String, description: &str, block: &ActionBlock) {
+    let paragraphs = block_paragraphs(description);
+    let header_end = paragraphs
+        .iter()
+        .filter(|(label, _, _)| *label != BlockLabel::Effect)
+        .map(|(_, range, _)| range.end)
+        .max();
+    let mut position = 0;
+    let mut header_written = false;
+    for (label, range, _) in paragraphs {
+        page.push_str(&description[position..range.start]);
+        position = range.end;
+        match (label, &block.effect) {
+            (BlockLabel::Effect, Some(effect)) => write!(page, "<p><strong>Effect</strong> {}</p>", effect),
+            (BlockLabel::Effect, None) => Ok(()),
+            _ if header_written => Ok(()),
+            _ => {
+                header_written = true;
+                block
+                    .header()
+                    .try_for_each(|(label, text)| write!(page, "<p><strong>{}</strong> {}</p>", label.as_ref(), text))
+            }
+        };
+        let rest = description[position..].trim_start();
+        if Some(position) == header_end && !rest.is_empty() && !rest.starts_with("<hr") && !rest.starts_with("</") {
+            page.push_str("<hr />");
+        }
+    }
+    page.push_str(&description[position..]);
+}
+
+/// One activity with a frequency, trigger, requirements, effect or degrees of success in the JSON export.
+#[derive(Serialize)]
+struct StructuredActivity<'a> {
+    name: &'a str,
+    /// The creature for creature abilities
+    creature: Option<&'a str>,
+    url: String,
+    /// Spells have a casting time instead
+    action_type: Option<ActionType>,
+    actions: Option<i32>,
+    #[serde(flatten)]
+    block: Option<&'a ActionBlock>,
+    #[serde(flatten)]
+    outcomes: &'a Outcomes,
+}
+
+impl StructuredActivity<'_> {
+    fn is_empty(&self) -> bool {
+        self.block.is_none_or(ActionBlock::is_empty) && self.outcomes.is_empty()
+    }
+}
+
+/// Writes all actions, feats, spells and creature abilities with structured parts to one file,
+/// e.g. to find all reactions with a certain kind of trigger or everything that happens on a critical failure.
+pub fn write_activities_json(
+    path: &str,
+    actions: &[(Action, HtmlPage)],
+    feats: &[(Feat, HtmlPage)],
+    spells: &[(Spell, HtmlPage)],
+    npcs: &[(Npc, HtmlPage)],
+) -> io::Result<()> {
+    let actions = actions.iter().map(|(a, _)| StructuredActivity {
+        name: &a.name,
+        creature: None,
+        url: format!("/action/{}", a.url_name()),
+        action_type: Some(a.action_type),
+        actions: a.number_of_actions,
+        block: Some(&a.block),
+        outcomes: &a.outcomes,
+    });
+    let feats = feats.iter().map(|(f, _)| StructuredActivity {
+        name: &f.name,
+        creature: None,
+        url: format!("/feat/{}", f.url_name()),
+        action_type: Some(f.action_type),
+        actions: f.actions,
+        block: Some(&f.block),
+        outcomes: &f.outcomes,
+    });
+    let spells = spells.iter().map(|(s, _)| StructuredActivity {
+        name: &s.name,
+        creature: None,
+        url: format!("/spell/{}", s.url_name()),
+        action_type: None,
+        actions: None,
+        block: None,
+        outcomes: &s.outcomes,
+    });
+    let abilities = npcs
+        .iter()
+        .filter_map(|(npc, _)| match npc {
+            Npc::Creature(c) => Some(c),
+            _ => None,
+        })
+        .flat_map(|c| {
+            c.actions.iter().map(move |a| StructuredActivity {
+                name: &a.name,
+                creature: Some(&c.name),
+                url: format!("/creature/{}", c.url_name()),
+                action_type: Some(a.action_type),
+                actions: a.number_of_actions,
+                block: Some(&a.block),
+                outcomes: &a.outcomes,
+            })
+        });
+    let activities: Vec<_> = actions
+        .chain(feats)
+        .chain(spells)
+        .chain(abilities)
+        .filter(|a| !a.is_empty())
+        .collect();
+    write_json(path, &activities)
 }
 
 #[cfg(test)]
@@ -48,7 +181,41 @@ mod tests {
     #[test]
     fn test_action_template() {
         let aid: Action = serde_json::from_str(&read_test_file("actions.db/aid.json")).expect("Deserialization failed");
-        assert_eq_ignore_linebreaks(&aid.render(()), include_str!("../../tests/html/aid.html"));
+        assert_eq_ignore_linebreaks(&aid.render(&Grants::default()), include_str!("../../tests/html/aid.html"));
+    }
+
+    #[test]
+    fn should_render_action_block_in_order() {
+        let description = "<p><strong>Requirement</strong> You are wielding a shield</p>\n<p><strong>Frequency</strong> once per day</p>\n<p>You brace.</p>\n<p><strong>Effect</strong> You gain resistance.</p>";
+        let mut page = String::new();
+        render_action_description(&mut page, description, &ActionBlock::parse(description));
+        assert_eq_ignore_linebreaks(
+            &page,
+            "<p><strong>Frequency</strong> once per day</p><p><strong>Requirements</strong> You are wielding a shield</p>\
+            <hr /><p>You brace.</p><p><strong>Effect</strong> You gain resistance.</p>",
+        );
+    }
+
+    #[test]
+    fn should_render_outcomes_as_table() {
+        let description = "<p>Attempt a check.</p>\n<hr />\n<p><strong>Success</strong> It works.</p>\n<p><strong>Critical Failure</strong> It breaks.</p>\n<p>Afterwards, rest.</p>";
+        assert_eq_ignore_linebreaks(
+            &render_outcomes(description, &Outcomes::parse(description)),
+            "<p>Attempt a check.</p><hr /><table class=\"outcomes\">\
+            <tr><td>Critical Success</td><td>—</td></tr>\
+            <tr><td>Success</td><td>It works.</td></tr>\
+            <tr><td>Failure</td><td>—</td></tr>\
+            <tr><td>Critical Failure</td><td>It breaks.</td></tr>\
+            </table><p>Afterwards, rest.</p>",
+        );
+    }
+
+    #[test]
+    fn should_keep_descriptions_without_block() {
+        let description = "<p>You brace.</p>\n<hr />\n<p><strong>Success</strong> You brace well.</p>";
+        let mut page = String::new();
+        render_action_description(&mut page, description, &ActionBlock::parse(description));
+        assert_eq!(page, description);
     }
 
     #[test]
@@ -56,9 +223,12 @@ mod tests {
         let aid: Action = serde_json::from_str(&read_test_file("actions.db/aid.json")).expect("Deserialization failed");
         let boarding_assault: Action =
             serde_json::from_str(&read_test_file("actions.db/boarding-assault.json")).expect("Deserialization failed");
-        let actions = vec![aid, boarding_assault].into_iter().map(|a| attach_html(a, ())).collect_vec();
+        let actions = vec![aid, boarding_assault]
+            .into_iter()
+            .map(|a| attach_html(a, &Grants::default()))
+            .collect_vec();
         assert_eq_ignore_linebreaks(
-            &Template::render_index(&actions),
+            &Template::render_index(&actions, &Grants::default()),
             include_str!("../../tests/html/action_index.html"),
         );
     }
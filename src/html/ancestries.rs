@@ -1,7 +1,8 @@
 use crate::{
-    data::{ancestries::Ancestry, traits::Rarity, HasName},
-    html::{render_traits, HtmlPage, Template},
+    data::{ancestries::Ancestry, feat_type::FeatType, feats::Feat, heritages::Heritage, traits::Rarity, HasName},
+    html::{feats::render_feats_by_level, inline_rarity_if_not_common, render_traits, HtmlPage, Template},
 };
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::{borrow::Cow, fmt::Write};
@@ -10,13 +11,18 @@ lazy_static! {
     static ref CURSIVE_FLAVOUR_TEXT: Regex = Regex::new("<em>(.*?)</em>").unwrap();
 }
 
-impl Template<()> for Ancestry {
-    fn render(&self, _: ()) -> Cow<'_, str> {
+/// The already rendered heritages and feats, so each ancestry can list its options.
+type AncestryData<'a> = (&'a [(Heritage, HtmlPage)], &'a [(Feat, HtmlPage)]);
+
+impl Template<AncestryData<'_>> for Ancestry {
+    fn render(&self, (heritages, feats): AncestryData) -> Cow<'_, str> {
         let mut page = String::with_capacity(10_000);
         write!(page, "<h1><a href=\"/ancestry/{}\">{}</a></h1><hr/>", self.url_name(), &self.name,);
         render_traits(&mut page, &self.traits);
         write!(page, "<b>Source </b>{}<br/>{}", self.source, &self.description,);
+        render_heritages(&mut page, &self.name, heritages);
         add_ancestry_feat_link(&self.url_name(), self.name(), &mut page);
+        render_ancestry_feats(&mut page, &self.name, feats);
         Cow::Owned(page)
     }
 
@@ -24,7 +30,7 @@ impl Template<()> for Ancestry {
         Cow::Borrowed("Ancestry")
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], _: AncestryData) -> String {
         let mut index = String::with_capacity(10_000);
         index.push_str(HEADER);
         index.push_str("<div id=\"list\">");
@@ -37,7 +43,7 @@ impl Template<()> for Ancestry {
         index
     }
 
-    fn header(&self) -> Option<Cow<'_, str>> {
+    fn header(&self, _: AncestryData) -> Option<Cow<'_, str>> {
         Some(Cow::Borrowed(HEADER))
     }
 }
@@ -73,6 +79,47 @@ fn render_rarity(elements: &[(Ancestry, HtmlPage)], rarity: Rarity, page: &mut S
     }
 }
 
+fn render_heritage_list<'a>(page: &mut String, title: &str, heritages: impl Iterator<Item = &'a Heritage>) {
+    let heritages = heritages.collect_vec();
+    if !heritages.is_empty() {
+        write!(page, "<h2>{}</h2><ul>", title);
+        for heritage in heritages {
+            write!(
+                page,
+                "<li><a href=\"/heritage/{}\">{}</a>{}</li>",
+                heritage.url_name(),
+                heritage.name,
+                inline_rarity_if_not_common(&heritage.traits.rarity)
+            );
+        }
+        page.push_str("</ul>");
+    }
+}
+
+fn render_heritages(page: &mut String, ancestry: &str, heritages: &[(Heritage, HtmlPage)]) {
+    render_heritage_list(
+        page,
+        "Heritages",
+        heritages.iter().map(|(h, _)| h).filter(|h| h.ancestry.as_deref() == Some(ancestry)),
+    );
+    render_heritage_list(
+        page,
+        "Versatile Heritages",
+        heritages.iter().map(|(h, _)| h).filter(|h| h.ancestry.is_none()),
+    );
+}
+
+fn render_ancestry_feats(page: &mut String, ancestry: &str, feats: &[(Feat, HtmlPage)]) {
+    let ancestry_trait = ancestry.to_lowercase();
+    render_feats_by_level(
+        page,
+        feats
+            .iter()
+            .map(|(f, _)| f)
+            .filter(|f| f.feat_type == FeatType::Ancestry && f.traits.misc.contains(&ancestry_trait)),
+    );
+}
+
 pub fn add_ancestry_feat_link(url_name: &str, name: &str, page: &mut String) {
     write!(
         page,
@@ -84,11 +131,67 @@ pub fn add_ancestry_feat_link(url_name: &str, name: &str, page: &mut String) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::{assert_eq_ignore_linebreaks, read_test_file};
+    use crate::{
+        data::traits::Traits,
+        tests::{assert_eq_ignore_linebreaks, read_test_file},
+    };
 
     #[test]
     fn ancestry_rendering_test() {
         let spooder: Ancestry = serde_json::from_str(&read_test_file("ancestries.db/anadi.json")).expect("Deserialization failed");
-        assert_eq_ignore_linebreaks(&spooder.render(()), include_str!("../../tests/html/spooder.html"));
+        assert_eq_ignore_linebreaks(&spooder.render((&[], &[])), include_str!("../../tests/html/spooder.html"));
+    }
+
+    fn traits(misc: &[&str], rarity: Rarity) -> Traits {
+        Traits {
+            misc: misc.iter().map(|t| t.to_string()).collect(),
+            rarity,
+            size: None,
+            alignment: None,
+        }
+    }
+
+    fn heritage(name: &str, ancestry: Option<&str>) -> (Heritage, HtmlPage) {
+        let heritage = Heritage {
+            name: name.to_string(),
+            description: String::new(),
+            ancestry: ancestry.map(String::from),
+            traits: traits(&[], Rarity::Common),
+            source: String::new(),
+            rules: vec![],
+        };
+        (heritage, HtmlPage::default())
+    }
+
+    fn feat(name: &str, level: i32, ancestry: &str) -> (Feat, HtmlPage) {
+        (
+            crate::tests::feat(name, FeatType::Ancestry, level, &[ancestry]),
+            HtmlPage::default(),
+        )
+    }
+
+    #[test]
+    fn should_list_heritages_and_ancestry_feats() {
+        let heritages = [
+            heritage("Cavern Elf", Some("Elf")),
+            heritage("Rock Dwarf", Some("Dwarf")),
+            heritage("Aasimar", None),
+        ];
+        let feats = [
+            feat("Elven Lore", 1, "elf"),
+            feat("Otherworldly Magic", 1, "elf"),
+            feat("Ancestral Longevity", 1, "elf"),
+            feat("Dwarven Lore", 1, "dwarf"),
+            feat("Ageless Patience", 5, "elf"),
+        ];
+        let mut page = String::new();
+        render_heritages(&mut page, "Elf", &heritages);
+        render_ancestry_feats(&mut page, "Elf", &feats);
+        assert!(page.contains("<h2>Heritages</h2><ul><li><a href=\"/heritage/cavern_elf\">Cavern Elf</a></li></ul>"));
+        assert!(page.contains("<h2>Versatile Heritages</h2><ul><li><a href=\"/heritage/aasimar\">Aasimar</a></li></ul>"));
+        assert!(!page.contains("Rock Dwarf"));
+        assert!(!page.contains("Dwarven Lore"));
+        assert!(page.find("<h3>Level 1</h3>") < page.find("<h3>Level 5</h3>"));
+        assert!(page.contains("Ageless Patience"));
     }
 }
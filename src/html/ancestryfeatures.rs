@@ -1,11 +1,11 @@
 use crate::{
-    data::{ancestry_features::AncestryFeature, traits::Translations, HasName},
-    html::{render_trait_legend, render_traits, HtmlPage, Template},
+    data::{ancestry_features::AncestryFeature, grants::Grants, traits::Translations, HasName},
+    html::{render_grants, render_rule_summary, render_trait_legend, render_traits, HtmlPage, Template},
 };
 use std::{borrow::Cow, fmt::Write};
 
-impl Template<&Translations> for AncestryFeature {
-    fn render(&self, trait_descriptions: &Translations) -> Cow<'_, str> {
+impl Template<(&Translations, &Grants)> for AncestryFeature {
+    fn render(&self, (trait_descriptions, grants): (&Translations, &Grants)) -> Cow<'_, str> {
         let mut page = String::with_capacity(5000);
         write!(
             page,
@@ -16,6 +16,8 @@ impl Template<&Translations> for AncestryFeature {
         render_traits(&mut page, &self.traits);
         page.push_str("<hr/>");
         page.push_str(&self.description);
+        render_rule_summary(&mut page, &self.rules);
+        render_grants(&mut page, grants, "ancestryfeature", self);
         page.push_str("<hr/>");
         render_trait_legend(&mut page, &self.traits, trait_descriptions);
         Cow::Owned(page)
@@ -25,7 +27,7 @@ impl Template<&Translations> for AncestryFeature {
         Cow::Borrowed("Ancestry Feature")
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], _: (&Translations, &Grants)) -> String {
         let mut page = String::with_capacity(50_000);
         page.push_str("<div id=\"gridlist\">");
         for (ancestryfeature, _) in elements {
@@ -50,6 +52,9 @@ mod tests {
     fn test_ancestry_feature_rendering() {
         let feature: AncestryFeature =
             serde_json::from_str(&read_test_file("ancestryfeatures.db/swim.json")).expect("Deserialization failed");
-        assert_eq_ignore_linebreaks(&feature.render(&TRANSLATIONS), include_str!("../../tests/html/azarketi_swim.html"));
+        assert_eq_ignore_linebreaks(
+            &feature.render((&TRANSLATIONS, &Grants::default())),
+            include_str!("../../tests/html/azarketi_swim.html"),
+        );
     }
 }
@@ -1,9 +1,10 @@
 use crate::{
-    data::{backgrounds::Background, HasName, ObjectName},
-    html::{inline_rarity_if_not_common, render_traits, HtmlPage, Template},
+    data::{ability_scores::AbilityScore, backgrounds::Background, skills::Skill, HasName, ObjectName},
+    html::{inline_rarity_if_not_common, render_traits, write_full_html_document, HtmlPage, Template},
 };
 use itertools::Itertools;
-use std::{borrow::Cow, fmt::Write};
+use std::{borrow::Cow, collections::BTreeMap, fmt::Write, io};
+use strum::IntoEnumIterator;
 
 impl Template<()> for Background {
     fn render(&self, _: ()) -> Cow<'_, str> {
@@ -25,35 +26,42 @@ impl Template<()> for Background {
         Cow::Owned(page)
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], _: ()) -> String {
         let mut index = String::with_capacity(10_000);
+        index.push_str(HEADER);
         index.push_str("<h1>Backgrounds</h1><hr/>");
-        index.push_str("<table class=\"overview\"><thead><tr><td>Name</td><td>Boost(s)</td><td>Lore</td><td>Feat</td></tr></thead>");
-        for (bg, _) in elements {
-            index.push_str("<tr><td><a href=\"/background/");
-            index.push_str(&bg.url_name());
-            index.push_str("\">");
-            index.push_str(&bg.name);
-            index.push_str("</a> ");
-            index.push_str(&inline_rarity_if_not_common(&bg.traits.rarity));
-            index.push_str("</td><td>");
-            index.push_str(&bg.boosts.iter().join(", "));
-            index.push_str("</td><td>");
-            index.push_str(&bg.lore);
-            index.push_str("</td><td>");
-            index.push_str(
-                &bg.feats
-                    .first()
-                    .map(|f| {
-                        let featname = ObjectName(f);
-                        format!("<a href=\"/feat/{}\">{}</a>", featname.url_name(), featname.without_variant())
-                    })
-                    .unwrap_or_else(|| String::from("none")),
+        render_background_table(&mut index, elements.iter().map(|(bg, _)| bg));
+        index
+    }
+
+    fn render_subindices(target: &str, elements: &[(Self, HtmlPage)], _: ()) -> io::Result<()> {
+        write_full_html_document(&format!("{}/finder", target), "Background Finder", &render_finder(elements))?;
+        for ability in AbilityScore::iter() {
+            for skill in finder_skills() {
+                let title = format!("{} Backgrounds with {}", ability.as_ref(), skill.as_ref());
+                let mut page = String::with_capacity(10_000);
+                page.push_str(HEADER);
+                write!(page, "<h1>{}</h1><hr/>", title);
+                render_background_table(&mut page, matching(elements, ability, &skill));
+                write_full_html_document(&format!("{}/{}", target, finder_url(ability, &skill)), &title, &page)?;
+            }
+        }
+        let by_feat = backgrounds_by_feat(elements);
+        write_full_html_document(&format!("{}/feats", target), "Backgrounds by Feat", &render_feat_index(&by_feat))?;
+        for (feat, backgrounds) in &by_feat {
+            let title = format!("Backgrounds granting {}", feat);
+            let mut page = String::with_capacity(10_000);
+            page.push_str(HEADER);
+            write!(
+                page,
+                "<h1>Backgrounds granting <a href=\"/feat/{}\">{}</a></h1><hr/>",
+                ObjectName(feat).url_name(),
+                feat
             );
-            index.push_str("</td></tr>");
+            render_background_table(&mut page, backgrounds.iter().copied());
+            write_full_html_document(&format!("{}/{}", target, feat_url(feat)), &title, &page)?;
         }
-        index.push_str("</table>");
-        index
+        Ok(())
     }
 
     fn category(&self) -> Cow<'_, str> {
@@ -61,10 +69,126 @@ impl Template<()> for Background {
     }
 }
 
+const HEADER: &str = r#"<div class="header">
+<span><a href="/background/"><div>All</div></a></span>
+<span><a href="/background/finder"><div>Finder</div></a></span>
+<span><a href="/background/feats"><div>By Feat</div></a></span>
+</div>"#;
+
+fn finder_skills() -> impl Iterator<Item = Skill> {
+    Skill::iter().filter(|s| !matches!(s, Skill::Lore(_)))
+}
+
+fn finder_url(ability: AbilityScore, skill: &Skill) -> String {
+    format!("{}_{}", ability.as_ref().to_lowercase(), skill.as_ref().to_lowercase())
+}
+
+fn feat_url(feat: &str) -> String {
+    format!("feat_{}", ObjectName(feat).url_name())
+}
+
+fn matching<'a>(elements: &'a [(Background, HtmlPage)], ability: AbilityScore, skill: &'a Skill) -> impl Iterator<Item = &'a Background> {
+    elements
+        .iter()
+        .map(|(bg, _)| bg)
+        .filter(move |bg| bg.boosts_ability(ability) && bg.skills.contains(skill))
+}
+
+/// A table of ability scores and skills with the number of backgrounds offering both, linking to the list of those backgrounds.
+fn render_finder(elements: &[(Background, HtmlPage)]) -> String {
+    let mut page = String::with_capacity(20_000);
+    page.push_str(HEADER);
+    page.push_str("<h1>Background Finder</h1><hr/>");
+    page.push_str("<p>Backgrounds by the ability boost and the skill they train. Free boosts are not counted.</p>");
+    page.push_str("<table class=\"overview\"><thead><tr><td>Skill</td>");
+    for ability in AbilityScore::iter() {
+        write!(page, "<td>{}</td>", ability.as_ref());
+    }
+    page.push_str("</tr></thead>");
+    for skill in finder_skills() {
+        write!(
+            page,
+            "<tr><td><a href=\"/skill/{}\">{}</a></td>",
+            skill.as_ref().to_lowercase(),
+            skill.as_ref()
+        );
+        for ability in AbilityScore::iter() {
+            match matching(elements, ability, &skill).count() {
+                0 => write!(page, "<td>0</td>"),
+                n => write!(page, "<td><a href=\"/background/{}\">{}</a></td>", finder_url(ability, &skill), n),
+            };
+        }
+        page.push_str("</tr>");
+    }
+    page.push_str("</table>");
+    page
+}
+
+fn backgrounds_by_feat(elements: &[(Background, HtmlPage)]) -> BTreeMap<&str, Vec<&Background>> {
+    let mut by_feat: BTreeMap<&str, Vec<&Background>> = BTreeMap::new();
+    for (bg, _) in elements {
+        for feat in &bg.feats {
+            by_feat.entry(feat).or_default().push(bg);
+        }
+    }
+    by_feat
+}
+
+fn render_feat_index(by_feat: &BTreeMap<&str, Vec<&Background>>) -> String {
+    let mut page = String::with_capacity(20_000);
+    page.push_str(HEADER);
+    page.push_str("<h1>Backgrounds by Feat</h1><hr/>");
+    page.push_str("<table class=\"overview\"><thead><tr><td>Feat</td><td>Backgrounds</td></tr></thead>");
+    for (feat, backgrounds) in by_feat {
+        write!(
+            page,
+            "<tr><td><a href=\"/feat/{}\">{}</a></td><td><a href=\"/background/{}\">{}</a></td></tr>",
+            ObjectName(feat).url_name(),
+            ObjectName(feat).without_variant(),
+            feat_url(feat),
+            backgrounds.len()
+        );
+    }
+    page.push_str("</table>");
+    page
+}
+
+fn render_background_table<'a>(index: &mut String, backgrounds: impl Iterator<Item = &'a Background>) {
+    index.push_str("<table class=\"overview\"><thead><tr><td>Name</td><td>Boost(s)</td><td>Lore</td><td>Feat</td></tr></thead>");
+    for bg in backgrounds {
+        index.push_str("<tr><td><a href=\"/background/");
+        index.push_str(&bg.url_name());
+        index.push_str("\">");
+        index.push_str(&bg.name);
+        index.push_str("</a> ");
+        index.push_str(&inline_rarity_if_not_common(&bg.traits.rarity));
+        index.push_str("</td><td>");
+        index.push_str(&bg.boosts.iter().join(", "));
+        index.push_str("</td><td>");
+        index.push_str(&bg.lore);
+        index.push_str("</td><td>");
+        index.push_str(
+            &bg.feats
+                .first()
+                .map(|f| {
+                    let featname = ObjectName(f);
+                    format!("<a href=\"/feat/{}\">{}</a>", featname.url_name(), featname.without_variant())
+                })
+                .unwrap_or_else(|| String::from("none")),
+        );
+        index.push_str("</td></tr>");
+    }
+    index.push_str("</table>");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
+        data::{
+            ability_scores::AbilityBoost,
+            traits::{Rarity, Traits},
+        },
         html::attach_html,
         tests::{assert_eq_ignore_linebreaks, read_test_file},
     };
@@ -92,8 +216,72 @@ mod tests {
             serde_json::from_str(&read_test_file("backgrounds.db/haunted.json")).expect("Deserialization of background failed");
         let bgs = vec![field_medic, haunted].into_iter().map(|bg| attach_html(bg, ())).collect_vec();
         assert_eq_ignore_linebreaks(
-            &Template::render_index(&bgs),
+            &Template::render_index(&bgs, ()),
             include_str!("../../tests/html/background_index.html"),
         );
     }
+
+    fn background(name: &str, boosts: Vec<AbilityBoost>, skill: Skill, feat: &str) -> (Background, HtmlPage) {
+        let background = Background {
+            name: name.to_string(),
+            boosts,
+            description: String::new(),
+            feats: vec![feat.to_string()],
+            lore: String::from("none"),
+            skills: vec![skill],
+            traits: Traits {
+                misc: vec![],
+                rarity: Rarity::Common,
+                size: None,
+                alignment: None,
+            },
+            source: String::new(),
+        };
+        (background, HtmlPage::default())
+    }
+
+    #[test]
+    fn should_find_backgrounds_by_boost_and_skill() {
+        let free = AbilityBoost(AbilityScore::iter().collect());
+        let backgrounds = [
+            background(
+                "Criminal",
+                vec![
+                    AbilityBoost(vec![AbilityScore::Dexterity, AbilityScore::Intelligence]),
+                    free.clone(),
+                ],
+                Skill::Stealth,
+                "Experienced Smuggler",
+            ),
+            background(
+                "Street Urchin",
+                vec![
+                    AbilityBoost(vec![AbilityScore::Dexterity, AbilityScore::Constitution]),
+                    free.clone(),
+                ],
+                Skill::Thievery,
+                "Pickpocket",
+            ),
+            background(
+                "Acrobat",
+                vec![AbilityBoost(vec![AbilityScore::Strength, AbilityScore::Dexterity]), free],
+                Skill::Acrobatics,
+                "Steady Balance",
+            ),
+        ];
+        let names = |ability, skill| matching(&backgrounds, ability, skill).map(|bg| bg.name.clone()).collect_vec();
+        assert_eq!(names(AbilityScore::Dexterity, &Skill::Stealth), vec!["Criminal"]);
+        assert!(names(AbilityScore::Charisma, &Skill::Stealth).is_empty());
+        assert_eq!(finder_url(AbilityScore::Dexterity, &Skill::Stealth), "dexterity_stealth");
+
+        let finder = render_finder(&backgrounds);
+        assert!(finder.contains("<td><a href=\"/background/dexterity_stealth\">1</a></td>"));
+
+        let by_feat = backgrounds_by_feat(&backgrounds);
+        assert_eq!(
+            by_feat["Pickpocket"].iter().map(|bg| bg.name.as_str()).collect_vec(),
+            vec!["Street Urchin"]
+        );
+        assert_eq!(feat_url("Experienced Smuggler"), "feat_experienced_smuggler");
+    }
 }
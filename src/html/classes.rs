@@ -2,52 +2,69 @@ use crate::{
     data::{
         ability_scores::AbilityScore,
         class_features::ClassFeature,
+        class_progression::{ClassProgression, FeatSlot, ABILITY_BOOST_LEVELS, MAX_LEVEL},
         classes::{AttackProficiencies, Class, ClassItem, DefensiveProficiencies},
+        feat_type::FeatType,
+        feats::Feat,
         proficiency::Proficiency,
-        HasName,
+        rules::Rule,
+        HasName, ObjectName,
+    },
+    html::{
+        feats::{render_feats_by_level, ARCHETYPE_TRAIT},
+        write_full_html_document, write_json, HtmlPage, Template,
     },
-    html::{HtmlPage, Template},
 };
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use std::{
     borrow::Cow,
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Write,
+    io,
 };
 
-const MAX_LEVEL: i32 = 20;
-const ABILITY_BOOST_LEVELS: &[i32] = &[5, 10, 15, 20];
-
 lazy_static! {
     static ref CHOICE_CLASS_SKILLS_REGEX: regex::Regex = regex::Regex::new("Trained in your choice of [\\w ]+").unwrap();
     static ref SUBCLASS_SKILLS_REGEX: regex::Regex =
         regex::Regex::new("Trained in (one|two|three) (or more )?skills? determined by[\\w '’]+").unwrap();
 }
 
+/// The already rendered class features and feats.
+type ClassData<'a> = (&'a [(ClassFeature, HtmlPage)], &'a [(Feat, HtmlPage)]);
+
 /*
  * pub description: String,
  * pub traits: Traits,
  */
-impl Template<&[(ClassFeature, HtmlPage)]> for Class {
-    fn render(&self, features: &[(ClassFeature, HtmlPage)]) -> Cow<'_, str> {
+impl Template<ClassData<'_>> for Class {
+    fn render(&self, (features, feats): ClassData) -> Cow<'_, str> {
         let mut page = String::with_capacity(10_000);
         write!(page, "<h1><a href=\"/class/{}\">{}</a></h1><hr/>", self.url_name(), self.name());
 
         add_hp(self.hp, &mut page);
         add_key_ability(&self.key_ability, &mut page);
         add_proficiencies(self, &mut page);
+        add_proficiency_chart(&ClassProgression::new(self, features.iter().map(|(f, _)| f)), &mut page);
 
         page.push_str("<h2>Class Features</h2><hr/>");
         let features_by_level = group_features_by_level(&self.class_features, features);
         add_feature_table(self, &features_by_level, &mut page);
+        write!(
+            page,
+            "<p><a href=\"/class/{}_progression\">Full {} progression including proficiency increases</a></p>",
+            self.url_name(),
+            self.name()
+        );
         for (_, p) in (1..=MAX_LEVEL).filter_map(|l| features_by_level.get(&l)).flatten() {
-            page.push_str(p.content.split("<h2>Traits</h2>").next().unwrap_or(&p.content));
+            page.push_str(without_trait_legend(p));
         }
+        add_subclass_choices(&find_subclass_choices(&self.name, &features_by_level, features), &mut page);
+        add_class_feats(self, feats, &mut page);
         Cow::Owned(page)
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], _: ClassData) -> String {
         let mut page = String::with_capacity(1000);
         page.push_str("<h1>Classes</h1><hr/><div id=\"list\">");
         for (class, _) in elements {
@@ -234,10 +251,90 @@ fn group_features_by_level<'a>(
     fbl
 }
 
+fn without_trait_legend(page: &HtmlPage) -> &str {
+    page.content.split("<h2>Traits</h2>").next().unwrap_or(&page.content)
+}
+
+/// A choice every member of a class makes, like a barbarian’s instinct, with its options.
+struct SubclassChoice<'a> {
+    feature: &'a ClassFeature,
+    options: Vec<&'a (ClassFeature, HtmlPage)>,
+}
+
+/// Features of the class that let you choose something are the subclass choices. The options are all features with the
+/// class trait at the same level that the class doesn’t get by default. If a class makes several choices at that level,
+/// an option belongs to the choice it mentions.
+fn find_subclass_choices<'a>(
+    class_name: &str,
+    features_by_level: &BTreeMap<i32, Vec<(&'a ClassFeature, &'a HtmlPage)>>,
+    all_features: &'a [(ClassFeature, HtmlPage)],
+) -> Vec<SubclassChoice<'a>> {
+    let class_trait = class_name.to_lowercase();
+    let own_features: HashSet<&str> = features_by_level.values().flatten().map(|(f, _)| f.name()).collect();
+    let mut choices = features_by_level
+        .values()
+        .flatten()
+        .filter(|(f, _)| f.rules.iter().any(|r| matches!(r, Rule::ChoiceSet { .. })))
+        .map(|&(feature, _)| SubclassChoice { feature, options: vec![] })
+        .collect_vec();
+    for option in all_features
+        .iter()
+        .filter(|(f, _)| f.traits.misc.contains(&class_trait) && !own_features.contains(f.name()))
+    {
+        let (feature, _) = option;
+        let at_level = choices.iter().positions(|c| c.feature.level == feature.level).collect_vec();
+        let mentions = |i: &&usize| {
+            let choice = choices[**i].feature.without_variant().to_lowercase();
+            feature.name.to_lowercase().contains(&choice) || feature.description.to_lowercase().contains(&choice)
+        };
+        if let Some(&i) = at_level.iter().find(mentions).or_else(|| at_level.first()) {
+            choices[i].options.push(option);
+        }
+    }
+    choices.retain(|c| !c.options.is_empty());
+    choices
+}
+
+/// Renders each choice as tabs, one per option. The tabs work without javascript by using radio buttons.
+fn add_subclass_choices(choices: &[SubclassChoice], page: &mut String) {
+    for choice in choices {
+        let group = choice.feature.url_name();
+        write!(page, "<h2>{}</h2><hr/><div class=\"tabs\">", choice.feature.without_variant());
+        for (i, (option, p)) in choice.options.iter().enumerate() {
+            write!(
+                page,
+                "<input type=\"radio\" name=\"{0}\" id=\"{0}-{1}\"{2}/><label for=\"{0}-{1}\">{3}</label><div class=\"tab\">",
+                group,
+                i,
+                if i == 0 { " checked" } else { "" },
+                option.without_variant()
+            );
+            page.push_str(without_trait_legend(p));
+            page.push_str("</div>");
+        }
+        page.push_str("</div>");
+    }
+}
+
+fn add_class_feats(class: &Class, feats: &[(Feat, HtmlPage)], page: &mut String) {
+    let class_trait = class.name.to_lowercase();
+    write!(
+        page,
+        "<h2 id=\"class-feats\">Class Feats</h2><hr/><p><a href=\"/feat/{}_tree\">Feat chains</a></p>",
+        class_trait
+    );
+    render_feats_by_level(
+        page,
+        feats.iter().map(|(f, _)| f).filter(|f| {
+            f.feat_type == FeatType::Class && f.traits.misc.contains(&class_trait) && !f.traits.misc.contains(&ARCHETYPE_TRAIT)
+        }),
+    );
+}
+
 fn add_feature_table(class: &Class, features_by_level: &BTreeMap<i32, Vec<(&ClassFeature, &HtmlPage)>>, page: &mut String) {
     page.push_str("<table class=\"overview\">");
     page.push_str("<thead><tr><td>Level</td><td>Features</td></tr></thead>");
-    let class_feat_link = format!("<a href=\"/feat/{}_index\">Class Feat</a>", class.url_name());
+    let class_feat_link = "<a href=\"#class-feats\">Class Feat</a>";
     for level in 1..=MAX_LEVEL {
         write!(page, "<tr><td>{}</td><td>", level);
         let mut features = Vec::new();
@@ -248,7 +345,7 @@ fn add_feature_table(class: &Class, features_by_level: &BTreeMap<i32, Vec<(&Clas
             features.push("<a href=\"/feat/aasimar_index\">Ancestry Feat</a>");
         }
         if class.class_feat_levels.contains(&level) {
-            features.push(&class_feat_link);
+            features.push(class_feat_link);
         }
         if class.general_feat_levels.contains(&level) {
             features.push("<a href=\"/feat/general_index\">General Feat</a>");
@@ -276,11 +373,112 @@ fn add_feature_table(class: &Class, features_by_level: &BTreeMap<i32, Vec<(&Clas
     page.push_str("</table>");
 }
 
+// Classes are rendered first, so the progression pages are written separately from the regular templates.
+pub fn render_progressions(target: &str, classes: &[(Class, HtmlPage)], features: &[(ClassFeature, HtmlPage)]) -> io::Result<()> {
+    for (class, _) in classes {
+        let progression = ClassProgression::new(class, features.iter().map(|(f, _)| f));
+        write_full_html_document(
+            &format!("{}/{}_progression", target, class.url_name()),
+            &format!("{} Progression", class.name()),
+            &render_progression(class, &progression),
+        )?;
+        write_json(&format!("{}/{}_progression.json", target, class.url_name()), &progression)?;
+    }
+    Ok(())
+}
+
+fn render_progression(class: &Class, progression: &ClassProgression) -> String {
+    let mut page = String::with_capacity(10_000);
+    write!(
+        page,
+        "<h1><a href=\"/class/{}\">{}</a> Progression</h1><hr/>",
+        class.url_name(),
+        class.name()
+    );
+    write!(
+        page,
+        "<p>This data is also available as <a href=\"/class/{}_progression.json\">JSON</a>.</p>",
+        class.url_name()
+    );
+    page.push_str("<table class=\"overview\">");
+    page.push_str("<thead><tr><td>Level</td><td>Class Features</td><td>Feats</td><td>Other</td><td>Proficiencies</td></tr></thead>");
+    for level in &progression.levels {
+        write!(page, "<tr><td>{}</td><td>", level.level);
+        page.push_str(
+            &level
+                .features
+                .iter()
+                .map(|f| ObjectName(f))
+                .map(|f| format!("<a href=\"/classfeature/{}\">{}</a>", f.url_name(), f.without_variant()))
+                .join(", "),
+        );
+        page.push_str("</td><td>");
+        page.push_str(&level.feat_slots.iter().map(|&slot| feat_slot_link(class, slot)).join(", "));
+        page.push_str("</td><td>");
+        let mut other = Vec::new();
+        if level.ability_boosts {
+            other.push("Ability Boosts");
+        }
+        if level.skill_increase {
+            other.push("Skill Increase");
+        }
+        page.push_str(&other.join(", "));
+        page.push_str("</td><td>");
+        page.push_str(
+            &level
+                .proficiencies
+                .iter()
+                .map(|p| format!("{} in {}", p.rank.as_ref(), p.statistic.label()))
+                .join(", "),
+        );
+        page.push_str("</td></tr>");
+    }
+    page.push_str("</table>");
+    page
+}
+
+fn add_proficiency_chart(progression: &ClassProgression, page: &mut String) {
+    page.push_str("<h2>Proficiency Progression</h2><hr/>");
+    page.push_str("<table class=\"overview proficiencies\"><thead><tr><td>Level</td>");
+    for level in &progression.levels {
+        write!(page, "<td>{}</td>", level.level);
+    }
+    page.push_str("</tr></thead>");
+    for (statistic, ranks) in progression.proficiency_table() {
+        write!(page, "<tr><td>{}</td>", statistic.label());
+        for rank in ranks {
+            write!(
+                page,
+                "<td class=\"rank-{}\" title=\"{}\">{}</td>",
+                rank as i32,
+                rank.as_ref(),
+                &rank.as_ref()[..1]
+            );
+        }
+        page.push_str("</tr>");
+    }
+    page.push_str("</table>");
+}
+
+fn feat_slot_link(class: &Class, slot: FeatSlot) -> String {
+    match slot {
+        FeatSlot::Ancestry => String::from("<a href=\"/feat/aasimar_index\">Ancestry Feat</a>"),
+        FeatSlot::Class => format!("<a href=\"/class/{}#class-feats\">Class Feat</a>", class.url_name()),
+        FeatSlot::General => String::from("<a href=\"/feat/general_index\">General Feat</a>"),
+        FeatSlot::Skill => String::from("<a href=\"/feat/acrobatics_index\">Skill Feat</a>"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        data::classes::OtherAttacksProficiencies,
+        data::{
+            action_type::ActionType,
+            class_progression::{LevelProgression, ProficiencyChange, Statistic},
+            classes::OtherAttacksProficiencies,
+            traits::{Rarity, Traits},
+        },
         tests::{assert_eq_ignore_linebreaks, read_test_file},
     };
 
@@ -371,4 +569,87 @@ mod tests {
         add_additional_skills_from_description(rogue_skills_text, &mut s);
         assert_eq!("Trained in one or more skills determined by your rogue's racket<br/>", s);
     }
+
+    #[test]
+    fn proficiency_chart_test() {
+        let progression = ClassProgression {
+            class: String::from("Tester"),
+            levels: vec![
+                LevelProgression {
+                    level: 1,
+                    proficiencies: vec![ProficiencyChange {
+                        statistic: Statistic::Reflex,
+                        rank: Proficiency::Trained,
+                    }],
+                    ..Default::default()
+                },
+                LevelProgression {
+                    level: 2,
+                    proficiencies: vec![ProficiencyChange {
+                        statistic: Statistic::Reflex,
+                        rank: Proficiency::Expert,
+                    }],
+                    ..Default::default()
+                },
+            ],
+        };
+        let mut s = String::new();
+        add_proficiency_chart(&progression, &mut s);
+        assert_eq!(
+            s,
+            "<h2>Proficiency Progression</h2><hr/><table class=\"overview proficiencies\"><thead><tr><td>Level</td><td>1</td><td>2</td></tr></thead>\
+            <tr><td>Reflex</td><td class=\"rank-1\" title=\"Trained\">T</td><td class=\"rank-2\" title=\"Expert\">E</td></tr></table>"
+        );
+    }
+
+    fn feature(name: &str, level: i32, description: &str, rules: Vec<Rule>) -> (ClassFeature, HtmlPage) {
+        let feature = ClassFeature {
+            name: name.to_string(),
+            description: description.to_string(),
+            feat_type: FeatType::ClassFeature,
+            action_type: ActionType::Passive,
+            number_of_actions: None,
+            level,
+            traits: Traits {
+                misc: vec![String::from("cleric")],
+                rarity: Rarity::Common,
+                size: None,
+                alignment: None,
+            },
+            rules,
+        };
+        let page = HtmlPage {
+            name: name.to_string(),
+            content: format!("<h1>{}</h1><h2>Traits</h2>legend", name),
+            ..HtmlPage::default()
+        };
+        (feature, page)
+    }
+
+    #[test]
+    fn should_find_subclass_choices() {
+        let choice = || vec![Rule::ChoiceSet { flag: None }];
+        let features = vec![
+            feature("Doctrine", 1, "", choice()),
+            feature("Deity", 1, "", choice()),
+            feature("Divine Font", 1, "", vec![]),
+            feature("Cloistered Cleric", 1, "This doctrine focuses on spells.", vec![]),
+            feature("Warpriest", 1, "A doctrine for fighting.", vec![]),
+            feature("Miraculous Spell", 19, "", vec![]),
+        ];
+        let by_level = BTreeMap::from([(1, features[..3].iter().map(|(f, p)| (f, p)).collect_vec())]);
+        let choices = find_subclass_choices("Cleric", &by_level, &features);
+        assert_eq!(choices.len(), 1);
+        assert_eq!(choices[0].feature.name, "Doctrine");
+        assert_eq!(
+            choices[0].options.iter().map(|(f, _)| f.name.as_str()).collect_vec(),
+            vec!["Cloistered Cleric", "Warpriest"]
+        );
+
+        let mut page = String::new();
+        add_subclass_choices(&choices, &mut page);
+        assert!(page
+            .starts_with("<h2>Doctrine</h2><hr/><div class=\"tabs\"><input type=\"radio\" name=\"doctrine\" id=\"doctrine-0\" checked/>"));
+        assert!(page.contains("<label for=\"doctrine-1\">Warpriest</label><div class=\"tab\"><h1>Warpriest</h1></div>"));
+    }
 }
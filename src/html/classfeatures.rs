@@ -1,11 +1,11 @@
 use crate::{
-    data::{class_features::ClassFeature, traits::Translations, HasName},
-    html::{render_trait_legend, render_traits, HtmlPage, Template},
+    data::{class_features::ClassFeature, grants::Grants, traits::Translations, HasName},
+    html::{render_grants, render_rule_summary, render_trait_legend, render_traits, HtmlPage, Template},
 };
 use std::{borrow::Cow, fmt::Write};
 
-impl Template<&Translations> for ClassFeature {
-    fn render(&self, trait_descriptions: &Translations) -> Cow<'_, str> {
+impl Template<(&Translations, &Grants)> for ClassFeature {
+    fn render(&self, (trait_descriptions, grants): (&Translations, &Grants)) -> Cow<'_, str> {
         let mut page = String::with_capacity(5000);
         write!(
             page,
@@ -21,12 +21,14 @@ impl Template<&Translations> for ClassFeature {
         );
         render_traits(&mut page, &self.traits);
         page.push_str(&self.description);
+        render_rule_summary(&mut page, &self.rules);
+        render_grants(&mut page, grants, "classfeature", self);
         page.push_str("<hr/>");
         render_trait_legend(&mut page, &self.traits, trait_descriptions);
         Cow::Owned(page)
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], _: (&Translations, &Grants)) -> String {
         let mut page = String::with_capacity(50_000);
         page.push_str("<div id=\"gridlist\">");
         for (classfeature, _) in elements {
@@ -55,6 +57,9 @@ mod tests {
     #[test]
     fn test_class_feature_rendering() {
         let feature: ClassFeature = serde_json::from_str(&read_test_file("classfeatures.db/evasion.json")).expect("Deserialization failed");
-        assert_eq_ignore_linebreaks(&feature.render(&TRANSLATIONS), include_str!("../../tests/html/evasion.html"));
+        assert_eq_ignore_linebreaks(
+            &feature.render((&TRANSLATIONS, &Grants::default())),
+            include_str!("../../tests/html/evasion.html"),
+        );
     }
 }
@@ -14,7 +14,7 @@ impl Template<()> for Condition {
         ))
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], _: ()) -> String {
         let mut index = String::with_capacity(50_000);
         for (_, page) in elements {
             index.push_str(&page.content);
@@ -48,7 +48,7 @@ mod tests {
         let deafened: Condition = serde_json::from_str(&read_test_file("conditions.db/deafened.json")).expect("Deserialization failed");
         let conditions = vec![blinded, deafened].into_iter().map(|c| attach_html(c, ())).collect_vec();
         assert_eq_ignore_linebreaks(
-            &Template::render_index(&conditions),
+            &Template::render_index(&conditions, ()),
             include_str!("../../tests/html/condition_index.html"),
         );
     }
@@ -0,0 +1,132 @@
+use crate::{
+    data::{
+        crafting::{crafting_dc, formula_price, CraftingInfo},
+        equipment::Equipment,
+        traits::Rarity,
+        HasName, ObjectName,
+    },
+    html::{render_traits_inline, write_full_html_document, HtmlPage},
+};
+use itertools::Itertools;
+use std::{fmt::Write, io};
+
+pub(crate) fn render_crafting_info(page: &mut String, item: &Equipment) {
+    let info = match CraftingInfo::new(item) {
+        Some(info) => info,
+        None => return,
+    };
+    write!(
+        page,
+        "<h2>Crafting</h2><b>Formula</b> {}; <b>Crafting DC</b> {}<br/><b>Requires</b> {}<br/>",
+        info.formula_price.to_string().trim_end(),
+        info.dc,
+        requirements(&info),
+    );
+    if let Some(requirements) = info.requirements {
+        write!(page, "<b>Craft Requirements</b> {}<br/>", requirements);
+    }
+}
+
+fn requirements(info: &CraftingInfo) -> String {
+    let proficiency = format!("{} in Crafting", info.proficiency.as_ref().to_lowercase());
+    let feats = info
+        .feats
+        .iter()
+        .map(|feat| format!(r#"<a href="/feat/{}">{}</a>"#, ObjectName(feat).url_name(), feat));
+    std::iter::once(proficiency).chain(feats).join(", ")
+}
+
+/// One page per item level with everything that can be Crafted at that level, plus an overview of formula prices and DCs.
+pub(crate) fn render_crafting_pages(target: &str, elements: &[(Equipment, HtmlPage)]) -> io::Result<()> {
+    let craftable = elements
+        .iter()
+        .filter_map(|(item, _)| Some((item, CraftingInfo::new(item)?)))
+        .into_group_map_by(|(item, _)| item.level);
+    let mut index = String::with_capacity(5_000);
+    index.push_str("<h1>Crafting</h1><hr/><table class=\"overview\">");
+    index.push_str(
+        "<thead><tr><td>Item Level</td><td>Formula</td><td>DC</td><td>Uncommon DC</td><td>Rare DC</td><td>Items</td></tr></thead>",
+    );
+    for level in 0..=20 {
+        let items = craftable.get(&level).map(Vec::as_slice).unwrap_or_default();
+        write!(
+            index,
+            r#"<tr><td><a href="crafting_{}">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>"#,
+            level,
+            level,
+            formula_price(level).unwrap_or_default(),
+            crafting_dc(level, Rarity::Common).unwrap_or_default(),
+            crafting_dc(level, Rarity::Uncommon).unwrap_or_default(),
+            crafting_dc(level, Rarity::Rare).unwrap_or_default(),
+            items.len(),
+        );
+        write_full_html_document(
+            &format!("{}/crafting_{}", target, level),
+            &format!("Level {} Crafting", level),
+            &render_crafting_level(level, items),
+        )?;
+    }
+    index.push_str("</table>");
+    write_full_html_document(&format!("{}/crafting", target), "Crafting", &index)
+}
+
+fn render_crafting_level(level: i32, items: &[(&Equipment, CraftingInfo)]) -> String {
+    let mut page = String::with_capacity(50_000);
+    write!(
+        page,
+        r#"<h1>Level {} Crafting</h1><hr/><a href="crafting">All Levels</a><br/><br/>"#,
+        level
+    );
+    page.push_str(r#"<table class="overview"><thead><tr><td>Name</td><td class="traitcolumn">Traits</td><td>Price</td><td>Formula</td><td>DC</td><td>Requires</td></tr></thead>"#);
+    for (item, info) in items.iter().sorted_by_key(|(item, _)| item.price) {
+        write!(
+            page,
+            r#"<tr><td><a href="{}">{}</a></td><td class="traitcolumn">"#,
+            item.url_name(),
+            item.name
+        );
+        render_traits_inline(&mut page, &item.traits);
+        write!(
+            page,
+            "</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            item.price,
+            info.formula_price,
+            info.dc,
+            requirements(info),
+        );
+    }
+    page.push_str("</table>");
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, level: i32, traits: &[&str], description: &str) -> Equipment {
+        let extra = serde_json::json!({ "description": { "value": description } });
+        crate::tests::equipment(name, "consumable", level, 30, "uncommon", traits, extra)
+    }
+
+    #[test]
+    fn should_render_crafting_info() {
+        let elixir = item(
+            "Elixir",
+            9,
+            &["alchemical", "elixir"],
+            "<p>You drink it.</p><p><strong>Craft Requirements</strong> Supply a troll tooth.</p>",
+        );
+        assert_eq!(elixir.description, "<p>You drink it.</p>");
+        let mut page = String::new();
+        render_crafting_info(&mut page, &elixir);
+        assert_eq!(
+            page,
+            "<h2>Crafting</h2><b>Formula</b> 35 gp; <b>Crafting DC</b> 28<br/>\
+            <b>Requires</b> master in Crafting, <a href=\"/feat/alchemical_crafting\">Alchemical Crafting</a><br/>\
+            <b>Craft Requirements</b> Supply a troll tooth.<br/>"
+        );
+        let mut page = String::new();
+        render_crafting_info(&mut page, &item("Orb", 9, &["artifact"], ""));
+        assert!(page.is_empty());
+    }
+}
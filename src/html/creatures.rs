@@ -2,26 +2,37 @@ use crate::{
     data::{
         action_type::ActionType,
         actions::Action,
-        creature::{Attack, Creature, Npc, OtherCreatureSpeed, SpellCasting},
+        creature::{Alignment, Attack, Creature, CreatureFamilies, Npc, OtherCreatureSpeed, SpellCasting},
         damage::CreatureDamage,
+        family_url_name,
+        size::Size,
         spells::Spell,
-        traits::{Traits, Translations},
+        traits::{Rarity, Traits, Translations},
         HasLevel, HasName,
     },
-    html::{render_trait_legend, render_traits, render_traits_inline, spells::spell_level_as_string, write_full_html_document, Template},
+    html::{
+        actions::render_action_description, render_outcomes, render_trait_legend, render_traits, render_traits_inline,
+        spells::spell_level_as_string, write_full_html_document, write_json, HtmlPage, Template, PARAGRAPH_REGEX,
+    },
 };
 use convert_case::{Case, Casing};
 use itertools::Itertools;
+use serde::Serialize;
 use std::{
     borrow::{Borrow, Cow},
-    collections::HashMap,
     fmt::{self, Display, Write},
+    io,
 };
+use strum::IntoEnumIterator;
+
+const FILTER_SCRIPT: &str = include_str!("../../static/creature_filter.js");
+
+pub type CreatureData<'a> = (&'a Translations, &'a CreatureFamilies);
 
-impl Template<&Translations> for Npc {
-    fn render(&self, descriptions: &Translations) -> Cow<'_, str> {
+impl Template<CreatureData<'_>> for Npc {
+    fn render(&self, (descriptions, families): CreatureData) -> Cow<'_, str> {
         if let Npc::Creature(c) = &self {
-            Cow::Owned(render_creature(c, descriptions))
+            Cow::Owned(render_creature(c, descriptions, families))
         } else {
             Cow::Borrowed("")
         }
@@ -31,45 +42,112 @@ impl Template<&Translations> for Npc {
         Cow::Borrowed("Creature")
     }
 
-    fn render_index(elements: &[(Self, super::HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], _: CreatureData) -> String {
         let mut page = String::with_capacity(250_000);
-        page.push_str("<h1>Creatures</h1><hr><br/>");
-        fill_index(
-            &mut page,
-            &elements
-                .iter()
-                .filter_map(|(n, _)| match n {
-                    Npc::Creature(c) => Some(c.borrow()),
-                    _ => None,
-                })
-                .collect_vec(),
+        page.push_str("<h1>Creatures</h1><hr><br/><a href=\"/creature/families\">Creature Families</a>");
+        page.push_str(
+            r#"<p><b>Level</b> <input id="creature-level-min" type="number" min="-1" max="25"/> to <input id="creature-level-max" type="number" min="-1" max="25"/>
+        <b>Size</b> <select id="creature-size"></select>
+        <b>Rarity</b> <select id="creature-rarity"></select>
+        <b>Alignment</b> <select id="creature-alignment"></select>
+        <b>Source</b> <select id="creature-source"></select>
+        <b>Traits</b> <input id="creature-traits" list="creature-trait-list" placeholder="dragon, fire"/><datalist id="creature-trait-list"></datalist>
+        <span id="creature-count"></span></p>"#,
         );
+        page.push_str("<div id=\"creature-index\">");
+        fill_index(&mut page, &creatures(elements));
+        page.push_str("</div><script>");
+        page.push_str(FILTER_SCRIPT);
+        page.push_str("</script>");
         page
     }
 
-    fn render_subindices(target: &str, elements: &[(Self, super::HtmlPage)]) -> std::io::Result<()> {
-        let mut by_trait = HashMap::new();
-        for (c, _) in elements {
-            if let Npc::Creature(creature) = c {
-                for t in &creature.traits.misc {
-                    by_trait.entry(t).or_insert_with(Vec::new).push(creature.borrow());
-                }
-            }
-        }
-        for (t, cs) in by_trait {
-            let mut page = String::with_capacity(250_000);
-            write!(page, "<h1>{} Creatures</h1><hr><br/>", t);
-            fill_index(&mut page, &cs);
-            write_full_html_document(
-                &format!("{}/trait_{}", target, t.to_lowercase()),
-                &format!("{} Creatures", t),
-                &page,
-            )?;
-        }
-        Ok(())
+    fn render_subindices(target: &str, elements: &[(Self, HtmlPage)], (_, families): CreatureData) -> io::Result<()> {
+        let creatures = creatures(elements);
+        write_json(&format!("{}/creatures.json", target), &creature_facets(&creatures))?;
+        render_families(target, &creatures, families)
     }
 }
 
+fn creatures(elements: &[(Npc, HtmlPage)]) -> Vec<&Creature> {
+    elements
+        .iter()
+        .filter_map(|(n, _)| match n {
+            Npc::Creature(c) => Some(c.borrow()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Everything the creature index can be filtered by.
+/// Enum values, sources and traits are stored once and referenced by their position to keep the file small.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+struct CreatureFacets<'a> {
+    sizes: Vec<&'static str>,
+    rarities: Vec<&'static str>,
+    alignments: Vec<&'static str>,
+    sources: Vec<&'a str>,
+    traits: Vec<&'a str>,
+    creatures: Vec<CreatureFacet<'a>>,
+}
+
+/// One entry per row of the creature index, in the same order.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+struct CreatureFacet<'a> {
+    name: &'a str,
+    url: String,
+    level: i32,
+    size: Option<usize>,
+    rarity: usize,
+    alignment: Option<usize>,
+    source: usize,
+    traits: Vec<usize>,
+}
+
+fn creature_facets<'a>(creatures: &[&'a Creature]) -> CreatureFacets<'a> {
+    let sources = creatures.iter().map(|c| c.source.as_str()).sorted().dedup().collect_vec();
+    let traits = creatures
+        .iter()
+        .flat_map(|c| c.traits.misc.iter().map(String::as_str))
+        .sorted()
+        .dedup()
+        .collect_vec();
+    let facets = creatures
+        .iter()
+        .map(|c| CreatureFacet {
+            name: &c.name,
+            url: format!("/creature/{}", c.url_name()),
+            level: c.level,
+            size: c.traits.size.map(enum_position),
+            rarity: enum_position(c.traits.rarity),
+            alignment: c.traits.alignment.map(enum_position),
+            source: sources.binary_search(&c.source.as_str()).unwrap_or_default(),
+            traits: c
+                .traits
+                .misc
+                .iter()
+                .filter_map(|t| traits.binary_search(&t.as_str()).ok())
+                .collect(),
+        })
+        .collect();
+    CreatureFacets {
+        sizes: enum_names::<Size>(),
+        rarities: enum_names::<Rarity>(),
+        alignments: enum_names::<Alignment>(),
+        sources,
+        traits,
+        creatures: facets,
+    }
+}
+
+fn enum_position<T: IntoEnumIterator + PartialEq>(value: T) -> usize {
+    T::iter().position(|v| v == value).unwrap_or_default()
+}
+
+fn enum_names<T: IntoEnumIterator + Into<&'static str>>() -> Vec<&'static str> {
+    T::iter().map(Into::into).collect()
+}
+
 fn fill_index(page: &mut String, elements: &[&Creature]) {
     page.push_str("<table class=\"overview\">");
     page.push_str("<thead><tr><td>Name</td><td class=\"traitcolumn\">Traits</td><td>Source</td><td>Level</td></tr></thead>");
@@ -86,7 +164,86 @@ fn fill_index(page: &mut String, elements: &[&Creature]) {
     page.push_str("</table>");
 }
 
-fn render_creature(creature: &Creature, descriptions: &Translations) -> String {
+/// One page per creature family with the flavor text and abilities all members share.
+fn render_families(target: &str, creatures: &[&Creature], families: &CreatureFamilies) -> io::Result<()> {
+    let mut index = String::with_capacity(20_000);
+    index.push_str("<h1>Creature Families</h1><hr/><table class=\"overview\">");
+    index.push_str("<thead><tr><td>Family</td><td>Members</td><td>Level</td></tr></thead>");
+    for family in families.names() {
+        let members = creatures
+            .iter()
+            .filter(|c| families.family(c) == Some(family))
+            .sorted_by_key(|c| c.level)
+            .copied()
+            .collect_vec();
+        let (Some(first), Some(last)) = (members.first(), members.last()) else {
+            continue;
+        };
+        write!(
+            index,
+            "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}–{}</td></tr>",
+            family_url_name(family),
+            family,
+            members.len(),
+            first.level,
+            last.level
+        );
+        write_full_html_document(
+            &format!("{}/{}", target, family_url_name(family)),
+            family,
+            &render_family(family, &members),
+        )?;
+    }
+    index.push_str("</table>");
+    write_full_html_document(&format!("{}/families", target), "Creature Families", &index)
+}
+
+fn render_family(family: &str, members: &[&Creature]) -> String {
+    let paragraphs = members
+        .iter()
+        .map(|c| {
+            PARAGRAPH_REGEX
+                .find_iter(c.flavor_text.as_deref().unwrap_or_default())
+                .map(|m| m.as_str())
+                .collect_vec()
+        })
+        .collect_vec();
+    let shared_flavor = paragraphs
+        .first()
+        .map(|first| first.iter().filter(|p| paragraphs.iter().all(|ps| ps.contains(p))).join(""))
+        .unwrap_or_default();
+    let shared_actions = members
+        .first()
+        .map(|first| {
+            first
+                .actions
+                .iter()
+                .filter(|a| members.iter().all(|m| m.actions.iter().any(|b| b.name == a.name)))
+                .cloned()
+                .collect_vec()
+        })
+        .unwrap_or_default();
+    let mut page = String::with_capacity(20_000);
+    write!(
+        page,
+        "<h1><a href=\"/creature/{}\">{}</a><span class=\"type\">Creature Family</span></h1><hr/>",
+        family_url_name(family),
+        family
+    );
+    if !shared_flavor.is_empty() {
+        page.push_str(&shared_flavor);
+        page.push_str("<hr/>");
+    }
+    if !shared_actions.is_empty() {
+        page.push_str("<h2>Family Abilities</h2>");
+        render_other_actions(&shared_actions, &mut page);
+    }
+    page.push_str("<h2>Members</h2>");
+    fill_index(&mut page, members);
+    page
+}
+
+fn render_creature(creature: &Creature, descriptions: &Translations, families: &CreatureFamilies) -> String {
     let mut page = String::with_capacity(20_000);
     write!(
         page,
@@ -99,7 +256,7 @@ fn render_creature(creature: &Creature, descriptions: &Translations) -> String {
     write!(
         page,
         "
-<b>Source</b> {}<br/>
+<b>Source</b> {}<br/>{}
 <b>Perception</b> {}{}{}<br/>
 <b>Languages</b> {}<br/>
 <b>Skills</b> {}<br/>
@@ -110,6 +267,10 @@ fn render_creature(creature: &Creature, descriptions: &Translations) -> String {
 <b>Speed</b> {}{}<br/>
 ",
         creature.source,
+        families
+            .family(creature)
+            .map(|f| format!("<b>Family</b> <a href=\"/creature/{}\">{}</a><br/>", family_url_name(f), f))
+            .unwrap_or_default(),
         sig(creature.perception),
         creature.perception,
         if !creature.senses.is_empty() {
@@ -314,7 +475,7 @@ fn render_other_actions(actions: &[Action], page: &mut String) {
             page.push_str(img);
         }
         add_traits(&action.traits, page, true, false);
-        page.push_str(&action.description);
+        render_action_description(page, &render_outcomes(&action.description, &action.outcomes), &action.block);
     }
     page.push_str("<hr/>");
 }
@@ -353,10 +514,12 @@ mod tests {
     use super::*;
     use crate::{
         data::{
-            creature::Npc,
+            action_type::ActionType,
+            actions::ActionBlock,
+            creature::{AbilityModifiers, CreatureSpeeds, Npc, SavingThrows},
             damage::DamageType,
+            outcomes::Outcomes,
             spells::{SpellComponents, SpellSchool, SpellTradition, SpellType},
-            traits::{Rarity, Traits},
         },
         tests::{assert_eq_ignore_linebreaks, read_test_file, TRANSLATIONS},
     };
@@ -373,7 +536,7 @@ mod tests {
             _ => panic!("Should have been a creature"),
         };
         assert_eq_ignore_linebreaks(
-            &render_creature(&dargon, &TRANSLATIONS),
+            &render_creature(&dargon, &TRANSLATIONS, &CreatureFamilies::default()),
             include_str!("../../tests/html/budget_dahak.html"),
         );
     }
@@ -434,6 +597,7 @@ mod tests {
                     cost: String::new(),
                     category: crate::data::spells::SpellCategory::Spell,
                     description: String::new(),
+                    outcomes: Outcomes::default(),
                     duration: String::new(),
                     level: 1,
                     range: "30 feet".to_string(),
@@ -473,6 +637,7 @@ mod tests {
                     cost: String::new(),
                     category: crate::data::spells::SpellCategory::Spell,
                     description: String::new(),
+                    outcomes: Outcomes::default(),
                     duration: String::new(),
                     level: 8,
                     range: String::new(),
@@ -514,4 +679,153 @@ mod tests {
         </p>",
         );
     }
+
+    fn creature(name: &str, level: i32, source: &str, traits: &[&str], size: Size, rarity: Rarity) -> Creature {
+        Creature {
+            name: name.to_string(),
+            ability_scores: AbilityModifiers {
+                strength: 0,
+                dexterity: 0,
+                constitution: 0,
+                intelligence: 0,
+                wisdom: 0,
+                charisma: 0,
+            },
+            ac: 10,
+            ac_details: None,
+            hp: 10,
+            hp_details: None,
+            perception: 0,
+            senses: String::new(),
+            speeds: CreatureSpeeds {
+                value: String::from("25 feet"),
+                other_speeds: vec![],
+            },
+            flavor_text: None,
+            level,
+            source: source.to_string(),
+            saves: SavingThrows {
+                reflex: 0,
+                fortitude: 0,
+                will: 0,
+                additional_save_modifier: None,
+            },
+            traits: Traits {
+                misc: traits.iter().map(|t| t.to_string()).collect(),
+                rarity,
+                alignment: Some(Alignment::CE),
+                size: Some(size),
+            },
+            resistances: vec![],
+            weaknesses: vec![],
+            immunities: vec![],
+            languages: vec![],
+            attacks: vec![],
+            skills: vec![],
+            spellcasting: vec![],
+            actions: vec![],
+        }
+    }
+
+    #[test]
+    fn should_collect_creature_facets() {
+        let dragon = creature(
+            "Young Red Dragon",
+            10,
+            "Bestiary",
+            &["dragon", "fire"],
+            Size::Large,
+            Rarity::Uncommon,
+        );
+        let goblin = creature(
+            "Goblin Warrior",
+            -1,
+            "Bestiary",
+            &["goblin", "humanoid"],
+            Size::Small,
+            Rarity::Common,
+        );
+        let facets = creature_facets(&[&dragon, &goblin]);
+        assert_eq!(facets.sources, vec!["Bestiary"]);
+        assert_eq!(facets.traits, vec!["dragon", "fire", "goblin", "humanoid"]);
+        assert_eq!(facets.sizes[1], "Small");
+        assert_eq!(facets.alignments[8], "CE");
+        assert_eq!(
+            facets.creatures[0],
+            CreatureFacet {
+                name: "Young Red Dragon",
+                url: String::from("/creature/young_red_dragon"),
+                level: 10,
+                size: Some(3),
+                rarity: 1,
+                alignment: Some(8),
+                source: 0,
+                traits: vec![0, 1],
+            }
+        );
+        assert_eq!(facets.creatures[1].traits, vec![2, 3]);
+        assert_eq!(facets.creatures[1].size, Some(1));
+    }
+
+    #[test]
+    fn should_group_creature_families() {
+        let frenzy = Action {
+            name: String::from("Draconic Frenzy"),
+            description: String::from("<p>The dragon makes two claw Strikes and one wing Strike.</p>"),
+            action_type: ActionType::Action,
+            number_of_actions: Some(2),
+            traits: Traits {
+                misc: vec![],
+                rarity: Rarity::Common,
+                alignment: None,
+                size: None,
+            },
+            block: ActionBlock::default(),
+            outcomes: Outcomes::default(),
+        };
+        let mut young = creature(
+            "Young Red Dragon",
+            10,
+            "Bestiary",
+            &["dragon", "fire"],
+            Size::Large,
+            Rarity::Uncommon,
+        );
+        let mut ancient = creature(
+            "Ancient Red Dragon",
+            19,
+            "Bestiary",
+            &["dragon", "fire"],
+            Size::Huge,
+            Rarity::Uncommon,
+        );
+        for dragon in [&mut young, &mut ancient] {
+            dragon.flavor_text = Some(String::from("<p>Red dragons are tyrants.</p>"));
+            dragon.actions.push(frenzy.clone());
+        }
+        ancient.flavor_text = Some(String::from("<p>Red dragons are tyrants.</p><p>This one is old.</p>"));
+        let clay = creature("Clay Golem", 10, "Bestiary", &["construct", "golem"], Size::Large, Rarity::Uncommon);
+        let flesh = creature("Flesh Golem", 8, "Bestiary", &["construct", "golem"], Size::Large, Rarity::Uncommon);
+        let goblin = creature(
+            "Goblin Warrior",
+            -1,
+            "Bestiary",
+            &["goblin", "humanoid"],
+            Size::Small,
+            Rarity::Common,
+        );
+        let families = CreatureFamilies::new([&ancient, &young, &clay, &flesh, &goblin]);
+        assert_eq!(families.family(&young), Some("Red Dragon"));
+        assert_eq!(families.family(&flesh), Some("Golem"));
+        assert_eq!(families.family(&goblin), None);
+        assert_eq!(families.names().collect_vec(), vec!["Golem", "Red Dragon"]);
+
+        let page = render_family("Red Dragon", &[&young, &ancient]);
+        assert!(page.starts_with(
+            "<h1><a href=\"/creature/family_red_dragon\">Red Dragon</a><span class=\"type\">Creature Family</span></h1><hr/>\
+            <p>Red dragons are tyrants.</p><hr/><h2>Family Abilities</h2><b>Draconic Frenzy</b>"
+        ));
+        assert!(!page.contains("This one is old"));
+        assert!(page.find("young_red_dragon") < page.find("ancient_red_dragon"));
+    }
 }
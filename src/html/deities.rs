@@ -1,38 +1,207 @@
 use crate::{
-    data::{deities::Deity, HasName},
-    html::{HtmlPage, Template},
+    data::{
+        deities::{Deity, DivineFont},
+        HasName, ObjectName,
+    },
+    html::{spells::spell_level_as_string, write_full_html_document, HtmlPage, Template},
 };
-use std::{borrow::Cow, fmt::Write};
+use itertools::Itertools;
+use std::{borrow::Cow, fmt::Write, io};
 
 impl Template<()> for Deity {
     fn render(&self, _: ()) -> Cow<'_, str> {
-        Cow::Borrowed(&self.content)
+        let mut page = String::with_capacity(10_000);
+        write!(
+            page,
+            "<h1><a href=\"/deity/{}\">{}</a><span class=\"type\">Deity</span></h1><hr/>",
+            self.url_name(),
+            self.name
+        );
+        render_stat_box(&mut page, self);
+        page.push_str(&self.content);
+        Cow::Owned(page)
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], _: ()) -> String {
         let mut index = String::with_capacity(10_000);
-        index.push_str("<div id=\"gridlist\">");
-        for (deity, _) in elements {
-            &write!(
-                index,
-                "<span><a href=\"{}\">{} [{}]</a></span>",
-                deity.url_name(),
-                deity.name(),
-                match deity.alignment {
-                    Some(a) => a.as_ref().to_owned(),
-                    None => "Unaligned".to_owned(),
-                }
-            );
-        }
-        index.push_str("</div>");
+        render_deity_header(&mut index, elements);
+        render_deity_grid(&mut index, elements.iter().map(|(d, _)| d));
         index
     }
 
+    fn render_subindices(target: &str, elements: &[(Self, HtmlPage)], _: ()) -> io::Result<()> {
+        for font in fonts(elements) {
+            let title = format!("{} Font Deities", font.as_ref());
+            let mut page = String::with_capacity(10_000);
+            render_deity_header(&mut page, elements);
+            write!(page, "<h1>{}</h1><hr/>", title);
+            render_deity_grid(&mut page, elements.iter().map(|(d, _)| d).filter(|d| d.divine_font.contains(&font)));
+            write_full_html_document(&format!("{}/{}", target, font_url(font)), &title, &page)?;
+        }
+        for domain in domains(elements) {
+            let title = format!("{} Domain", domain);
+            let mut page = String::with_capacity(10_000);
+            render_deity_header(&mut page, elements);
+            write!(page, "<h1>{}</h1><hr/><h2>Deities with this domain</h2>", title);
+            render_deity_grid(&mut page, elements.iter().map(|(d, _)| d).filter(|d| d.domains.contains(domain)));
+            let alternate = elements
+                .iter()
+                .map(|(d, _)| d)
+                .filter(|d| d.alternate_domains.contains(domain))
+                .collect_vec();
+            if !alternate.is_empty() {
+                page.push_str("<h2>Deities with this alternate domain</h2>");
+                render_deity_grid(&mut page, alternate.into_iter());
+            }
+            write_full_html_document(&format!("{}/{}", target, domain_url(domain)), &title, &page)?;
+        }
+        Ok(())
+    }
+
     fn category(&self) -> Cow<'_, str> {
         Cow::Borrowed("Deity")
     }
 }
 
+fn font_url(font: DivineFont) -> String {
+    format!("font_{}", font.as_ref().to_lowercase())
+}
+
+fn domain_url(domain: &str) -> String {
+    format!("domain_{}", ObjectName(domain).url_name())
+}
+
+fn fonts(elements: &[(Deity, HtmlPage)]) -> Vec<DivineFont> {
+    elements
+        .iter()
+        .flat_map(|(d, _)| &d.divine_font)
+        .copied()
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+fn domains(elements: &[(Deity, HtmlPage)]) -> Vec<&String> {
+    elements
+        .iter()
+        .flat_map(|(d, _)| d.domains.iter().chain(&d.alternate_domains))
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// Links to all fonts and domains so the deities can be filtered by them.
+fn render_deity_header(page: &mut String, elements: &[(Deity, HtmlPage)]) {
+    page.push_str(r#"<div class="header">"#);
+    page.push_str(r#"<span><a href="/deity/"><div>All</div></a></span>"#);
+    for font in fonts(elements) {
+        write!(
+            page,
+            r#"<span><a href="/deity/{}"><div>{}</div></a></span>"#,
+            font_url(font),
+            font.as_ref()
+        );
+    }
+    page.push_str("</div>");
+    let domains = domains(elements);
+    if !domains.is_empty() {
+        page.push_str("<p><b>Domains</b> ");
+        page.push_str(
+            &domains
+                .iter()
+                .map(|d| format!("<a href=\"/deity/{}\">{}</a>", domain_url(d), d))
+                .join(", "),
+        );
+        page.push_str("</p>");
+    }
+}
+
+fn render_deity_grid<'a>(page: &mut String, deities: impl Iterator<Item = &'a Deity>) {
+    page.push_str("<div id=\"gridlist\">");
+    for deity in deities {
+        write!(
+            page,
+            "<span><a href=\"{}\">{} [{}]</a></span>",
+            deity.url_name(),
+            deity.name(),
+            match deity.alignment {
+                Some(a) => a.as_ref().to_owned(),
+                None => "Unaligned".to_owned(),
+            }
+        );
+    }
+    page.push_str("</div>");
+}
+
+fn render_stat_box(page: &mut String, deity: &Deity) {
+    if let Some(alignment) = deity.alignment {
+        write!(page, "<b>Alignment</b> {}<br/>", alignment.as_ref());
+    }
+    if !deity.follower_alignments.is_empty() {
+        write!(
+            page,
+            "<b>Follower Alignments</b> {}<br/>",
+            deity.follower_alignments.iter().map(|a| a.as_ref()).join(", ")
+        );
+    }
+    if !deity.divine_font.is_empty() {
+        let fonts = deity
+            .divine_font
+            .iter()
+            .map(|&f| format!("<a href=\"/deity/{}\">{}</a>", font_url(f), f.as_ref()))
+            .join(" or ");
+        write!(page, "<b>Divine Font</b> {}<br/>", fonts);
+    }
+    if !deity.divine_skills.is_empty() {
+        write!(
+            page,
+            "<b>Divine Skill</b> {}<br/>",
+            deity.divine_skills.iter().map(|s| s.as_ref()).join(", ")
+        );
+    }
+    if !deity.favored_weapons.is_empty() {
+        let weapons = deity
+            .favored_weapons
+            .iter()
+            .map(|w| format!("<a href=\"/item/{}\">{}</a>", ObjectName(w).url_name(), w))
+            .join(", ");
+        write!(page, "<b>Favored Weapon</b> {}<br/>", weapons);
+    }
+    for (label, domains) in [("Domains", &deity.domains), ("Alternate Domains", &deity.alternate_domains)] {
+        if !domains.is_empty() {
+            let links = domains
+                .iter()
+                .map(|d| format!("<a href=\"/deity/{}\">{}</a>", domain_url(d), d))
+                .join(", ");
+            write!(page, "<b>{}</b> {}<br/>", label, links);
+        }
+    }
+    if !deity.cleric_spells.is_empty() {
+        let spells = deity
+            .cleric_spells
+            .iter()
+            .map(|(level, spell)| {
+                format!(
+                    "{}: <a href=\"/spell/{}\">{}</a>",
+                    spell_level_as_string(*level),
+                    ObjectName(spell).url_name(),
+                    spell
+                )
+            })
+            .join(", ");
+        write!(page, "<b>Cleric Spells</b> {}<br/>", spells);
+    }
+    if let Some(sanctification) = &deity.sanctification {
+        write!(
+            page,
+            "<b>Sanctification</b> {} be {}<br/>",
+            sanctification.modal,
+            sanctification.what.join(" or ")
+        );
+    }
+    page.push_str("<hr/>");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,6 +222,39 @@ mod tests {
         let asmodeus: Deity = serde_json::from_str(&read_test_file("deities.db/asmodeus.json")).expect("Deserialization failed");
         let pharasma: Deity = serde_json::from_str(&read_test_file("deities.db/pharasma.json")).expect("Deserialization failed");
         let deities = vec![asmodeus, pharasma].into_iter().map(|s| attach_html(s, ())).collect_vec();
-        assert_eq_ignore_linebreaks(&Template::render_index(&deities), include_str!("../../tests/html/deity_index.html"));
+        assert_eq_ignore_linebreaks(
+            &Template::render_index(&deities, ()),
+            include_str!("../../tests/html/deity_index.html"),
+        );
+    }
+
+    #[test]
+    fn should_render_stat_box() {
+        let json = r#"{
+            "name": "Gorum",
+            "system": {
+                "description": { "value": "<p>Our Lord in Iron</p>" },
+                "alignment": { "own": "CN", "follower": ["CN", "CE"] },
+                "font": ["harm", "heal"],
+                "skill": "athletics",
+                "weapons": ["greatsword"],
+                "domains": { "primary": ["strength", "zeal"], "alternate": ["might"] },
+                "spells": { "1": "Compendium.pf2e.spells-srd.Item.True Strike" }
+            }
+        }"#;
+        let gorum: Deity = serde_json::from_str(json).expect("Deserialization failed");
+        assert_eq_ignore_linebreaks(
+            &gorum.render(()),
+            "<h1><a href=\"/deity/gorum\">Gorum</a><span class=\"type\">Deity</span></h1><hr/>\
+            <b>Alignment</b> CN<br/>\
+            <b>Follower Alignments</b> CN, CE<br/>\
+            <b>Divine Font</b> <a href=\"/deity/font_harm\">Harm</a> or <a href=\"/deity/font_heal\">Heal</a><br/>\
+            <b>Divine Skill</b> Athletics<br/>\
+            <b>Favored Weapon</b> <a href=\"/item/greatsword\">Greatsword</a><br/>\
+            <b>Domains</b> <a href=\"/deity/domain_strength\">Strength</a>, <a href=\"/deity/domain_zeal\">Zeal</a><br/>\
+            <b>Alternate Domains</b> <a href=\"/deity/domain_might\">Might</a><br/>\
+            <b>Cleric Spells</b> 1st Level: <a href=\"/spell/true_strike\">True Strike</a><br/>\
+            <hr/><p>Our Lord in Iron</p>",
+        );
     }
 }
@@ -2,23 +2,37 @@ use crate::{
     data::{
         damage::EquipmentDamageWithSplash,
         ensure_trailing_unit,
-        equipment::{Equipment, ItemType, ProficiencyGroup, Weight},
-        traits::Translations,
+        equipment::{ArmorStats, Equipment, ItemFamilies, ItemType, Price, ProficiencyGroup, WeaponGroup, Weight},
+        family_url_name,
+        grants::Grants,
+        traits::{Rarity, Translations},
         HasName,
     },
-    html::{render_trait_legend, render_traits, render_traits_inline, write_full_html_document, HtmlPage, Template},
+    html::{
+        crafting::{render_crafting_info, render_crafting_pages},
+        render_grants, render_rule_summary, render_trait_legend, render_traits, render_traits_inline,
+        runes::render_rune_builder,
+        spell_items::render_staff_spells,
+        treasure::render_loot_page,
+        write_full_html_document, write_json, HtmlPage, Template, PARAGRAPH_REGEX,
+    },
 };
 use itertools::Itertools;
-use std::{borrow::Cow, fmt::Write};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::{borrow::Cow, collections::BTreeMap, fmt::Write, io};
 use strum::IntoEnumIterator;
 
-/*
- * Missing:
- * pub group: WeaponGroup,
- * pub usage: Option<ItemUsage>,
-*/
-impl Template<&Translations> for Equipment {
-    fn render(&self, trait_descriptions: &Translations) -> Cow<'_, str> {
+lazy_static! {
+    static ref BONUS_REGEX: Regex = Regex::new(r"\+\d+ (item|circumstance|status) bonus").unwrap();
+    static ref DC_REGEX: Regex = Regex::new(r"DC \d+").unwrap();
+}
+
+pub type EquipmentData<'a> = (&'a Translations, &'a Grants, &'a ItemFamilies);
+
+impl Template<EquipmentData<'_>> for Equipment {
+    fn render(&self, (trait_descriptions, grants, families): EquipmentData) -> Cow<'_, str> {
         let mut page = String::with_capacity(1000);
         write!(
             page,
@@ -32,6 +46,15 @@ impl Template<&Translations> for Equipment {
         if !self.source.is_empty() {
             write!(page, "<b>Source</b> {}<br/>", self.source);
         }
+        if let Some(family) = families.family(self) {
+            write!(
+                page,
+                "<b>Variants</b> <a href=\"/item/{}\">{}</a> ({} variants)<br/>",
+                family_url_name(family),
+                family,
+                families.variants(family)
+            );
+        }
         if self.max_hp != 0 {
             page.push_str("<b>Hit points</b> ");
             page.push_str(&self.max_hp.to_string());
@@ -52,6 +75,18 @@ impl Template<&Translations> for Equipment {
         if self.category != ProficiencyGroup::NoProficiency {
             write!(page, "<b>Type</b> {}<br/>", self.category.as_ref());
         }
+        render_weapon_stats(&mut page, self);
+        if let Some(armor) = &self.armor {
+            render_armor_stats(&mut page, armor, self.group);
+        }
+        if let Some(shield) = &self.shield {
+            write!(
+                page,
+                "<b>AC Bonus</b> {:+}; <b>Speed Penalty</b> {}<br/>",
+                shield.ac_bonus,
+                speed_penalty(shield.speed_penalty)
+            );
+        }
         if self.range != 0 {
             page.push_str("<b>Range</b> ");
             page.push_str(&ensure_trailing_unit(&self.range.to_string()));
@@ -65,8 +100,18 @@ impl Template<&Translations> for Equipment {
             page.push_str(&self.weight.to_string());
             page.push_str("<br/>");
         }
+        if let Some(specialization) = self.critical_specialization() {
+            write!(page, "<b>Critical Specialization</b> {}<br/>", specialization);
+        }
         page.push_str("<hr/>");
-        page.push_str(&self.description);
+        if self.traits.misc.iter().any(|t| t == "staff") {
+            page.push_str(&render_staff_spells(&self.description));
+        } else {
+            page.push_str(&self.description);
+        }
+        render_crafting_info(&mut page, self);
+        render_rule_summary(&mut page, &self.rules);
+        render_grants(&mut page, grants, "item", self);
         render_trait_legend(&mut page, &self.traits, trait_descriptions);
         Cow::Owned(page)
     }
@@ -75,35 +120,194 @@ impl Template<&Translations> for Equipment {
         Cow::Borrowed(self.item_type.into())
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
-        render_filtered_index("Equipment", elements, |_| true)
+    fn price_copper(&self) -> Option<u32> {
+        // Free items too, so filters like `price_copper < 5000` include them
+        Some(self.price.as_copper())
+    }
+
+    fn render_index(elements: &[(Self, HtmlPage)], (_, _, families): EquipmentData) -> String {
+        render_filtered_index("Equipment", elements, families, |_| true)
     }
 
-    fn render_subindices(target: &str, elements: &[(Self, HtmlPage)]) -> std::io::Result<()> {
-        for category in ItemType::iter().filter(|&t| t != ItemType::Weapon) {
+    fn render_subindices(target: &str, elements: &[(Self, HtmlPage)], (_, _, families): EquipmentData) -> std::io::Result<()> {
+        for category in ItemType::iter().filter(|t| ![ItemType::Weapon, ItemType::Armor, ItemType::Shield].contains(t)) {
             write_full_html_document(
                 &format!("{}/{}_index", target, category.as_ref()),
                 &format!("{} List", category.as_ref()),
-                &render_filtered_index(category.as_ref(), elements, |e| e.item_type == category),
+                &render_filtered_index(category.as_ref(), elements, families, |e| e.item_type == category),
             )?;
         }
+        render_families(target, elements, families)?;
         write_full_html_document(
             &format!("{}/{}_index", target, ItemType::Weapon.as_ref()),
             &format!("{} List", ItemType::Weapon.as_ref()),
             &render_weapon_index(elements),
         )?;
-        for t in elements.iter().flat_map(|(i, _)| &i.traits.misc).unique() {
-            let title = &format!("{} Items", t);
-            write_full_html_document(
-                &format!("{}/trait_{}", target, t.to_lowercase()),
-                title,
-                &render_filtered_index(title, elements, |e| e.traits.misc.contains(t)),
-            )?;
-        }
+        render_weapon_groups(target, elements)?;
+        render_rune_builder(target, elements)?;
+        render_loot_page(target, elements)?;
+        render_crafting_pages(target, elements)?;
+        write_full_html_document(
+            &format!("{}/price_outliers", target),
+            "Price Outliers",
+            &render_price_outliers(elements),
+        )?;
+        write_json(&format!("{}/items.json", target), &item_export(elements))?;
+        write_full_html_document(
+            &format!("{}/{}_index", target, ItemType::Armor.as_ref()),
+            &format!("{} List", ItemType::Armor.as_ref()),
+            &render_armor_index(elements),
+        )?;
+        write_full_html_document(
+            &format!("{}/{}_index", target, ItemType::Shield.as_ref()),
+            &format!("{} List", ItemType::Shield.as_ref()),
+            &render_shield_index(elements),
+        )?;
         Ok(())
     }
 }
 
+impl Equipment {
+    fn critical_specialization(&self) -> Option<&'static str> {
+        if self.item_type == ItemType::Weapon {
+            self.group.critical_specialization()
+        } else {
+            None
+        }
+    }
+}
+
+/// Group, hands, reload and ammunition. The usage is shown for all items.
+fn render_weapon_stats(page: &mut String, item: &Equipment) {
+    if item.item_type == ItemType::Weapon && item.group != WeaponGroup::NotAWeapon {
+        write!(
+            page,
+            "<b>Group</b> <a href=\"/item/{}\">{}</a><br/>",
+            group_url(item.group),
+            item.group.as_ref()
+        );
+    }
+    match (item.usage, item.usage.and_then(|u| u.hands())) {
+        (_, Some(hands)) => write!(page, "<b>Hands</b> {}<br/>", hands),
+        (Some(usage), None) => write!(page, "<b>Usage</b> {}<br/>", usage.text()),
+        (None, None) => Ok(()),
+    };
+    if let Some(reload) = item.reload {
+        write!(page, "<b>Reload</b> {}<br/>", reload);
+    }
+    if let Some(ammunition) = item.ammunition() {
+        write!(page, "<b>Ammunition</b> {}<br/>", ammunition);
+    }
+}
+
+fn group_url(group: WeaponGroup) -> String {
+    format!("group_{}", group.as_ref().to_lowercase())
+}
+
+fn weapon_groups(elements: &[(Equipment, HtmlPage)]) -> Vec<WeaponGroup> {
+    elements
+        .iter()
+        .filter(|(i, _)| i.item_type == ItemType::Weapon && i.group != WeaponGroup::NotAWeapon)
+        .map(|(i, _)| i.group)
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// One hub page per weapon group with its critical specialization and all weapons in it.
+fn render_weapon_groups(target: &str, elements: &[(Equipment, HtmlPage)]) -> io::Result<()> {
+    let groups = weapon_groups(elements);
+    for &group in &groups {
+        let mut page = String::with_capacity(20_000);
+        add_item_header(&mut page);
+        write!(page, "<h1>{} Weapons</h1><hr/>", group.as_ref());
+        if let Some(specialization) = group.critical_specialization() {
+            write!(page, "<p><b>Critical Specialization</b> {}</p>", specialization);
+        }
+        page.push_str("<table class=\"overview\">");
+        page.push_str("<thead><tr><td>Name</td><td class=\"traitcolumn\">Traits</td><td>Damage</td><td>Hands</td><td>Type</td><td>Range</td><td>Level</td></tr></thead>");
+        for (item, _) in elements.iter().filter(|(i, _)| i.item_type == ItemType::Weapon && i.group == group) {
+            write!(
+                page,
+                "<tr><td><a href=\"{}\">{}</a></td><td class=\"traitcolumn\">",
+                item.url_name(),
+                item.name,
+            );
+            render_traits_inline(&mut page, &item.traits);
+            write!(
+                page,
+                "</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                item.damage.clone().map(|d| d.to_string()).unwrap_or_default(),
+                item.usage.and_then(|u| u.hands()).map(|h| h.to_string()).unwrap_or_default(),
+                item.category.as_ref(),
+                weapon_range(item),
+                item.level,
+            );
+        }
+        page.push_str("</table>");
+        write_full_html_document(
+            &format!("{}/{}", target, group_url(group)),
+            &format!("{} Weapons", group.as_ref()),
+            &page,
+        )?;
+    }
+    let mut index = String::with_capacity(5_000);
+    add_item_header(&mut index);
+    index.push_str("<h1>Weapon Groups</h1><hr/><div id=\"gridlist\">");
+    for group in groups {
+        write!(index, "<span><a href=\"{}\">{}</a></span>", group_url(group), group.as_ref());
+    }
+    index.push_str("</div>");
+    write_full_html_document(&format!("{}/weapon_groups", target), "Weapon Groups", &index)
+}
+
+fn weapon_range(item: &Equipment) -> String {
+    if item.range == 0 {
+        "Melee".to_string()
+    } else {
+        format!("{} feet", item.range)
+    }
+}
+
+fn penalty(n: i32) -> String {
+    if n == 0 {
+        String::from("—")
+    } else {
+        n.to_string()
+    }
+}
+
+fn speed_penalty(n: i32) -> String {
+    if n == 0 {
+        String::from("—")
+    } else {
+        format!("{} feet", n)
+    }
+}
+
+fn dex_cap(armor: &ArmorStats) -> String {
+    armor.dex_cap.map(|d| format!("{:+}", d)).unwrap_or_else(|| String::from("—"))
+}
+
+fn strength(armor: &ArmorStats) -> String {
+    armor.strength.map(|s| s.to_string()).unwrap_or_else(|| String::from("—"))
+}
+
+fn render_armor_stats(page: &mut String, armor: &ArmorStats, group: WeaponGroup) {
+    write!(
+        page,
+        "<b>AC Bonus</b> {:+}; <b>Dex Cap</b> {}; <b>Strength</b> {}<br/><b>Check Penalty</b> {}; <b>Speed Penalty</b> {}<br/>",
+        armor.ac_bonus,
+        dex_cap(armor),
+        strength(armor),
+        penalty(armor.check_penalty),
+        speed_penalty(armor.speed_penalty)
+    );
+    if group != WeaponGroup::NotAWeapon {
+        write!(page, "<b>Group</b> {}<br/>", group.as_ref());
+    }
+}
+
 fn add_item_header(page: &mut String) {
     page.push_str(r#"<div class="header">"#);
     page.push_str(r#"<span><a href="index.html"><div>All</div></a></span>"#);
@@ -115,10 +319,32 @@ fn add_item_header(page: &mut String) {
             item_type.as_ref()
         );
     }
+    page.push_str(r#"<span><a href="/item/builder"><div>Rune Builder</div></a></span>"#);
+    page.push_str(r#"<span><a href="/item/price_outliers"><div>Price Outliers</div></a></span>"#);
+    page.push_str(r#"<span><a href="/item/loot"><div>Treasure</div></a></span>"#);
+    page.push_str(r#"<span><a href="/item/crafting"><div>Crafting</div></a></span>"#);
     page.push_str("</div>");
 }
 
-fn render_filtered_index<F: FnMut(&Equipment) -> bool>(title: &str, elements: &[(Equipment, HtmlPage)], mut filter: F) -> String {
+/// Variants of the same item are combined into one row that links to the family page.
+fn render_filtered_index<F: FnMut(&Equipment) -> bool>(
+    title: &str,
+    elements: &[(Equipment, HtmlPage)],
+    families: &ItemFamilies,
+    mut filter: F,
+) -> String {
+    let items = elements
+        .iter()
+        .map(|(i, _)| i)
+        .filter(|i| filter(i))
+        .sorted_by_key(|i| (i.level, i.price))
+        .collect_vec();
+    let mut variants: BTreeMap<&str, Vec<&Equipment>> = BTreeMap::new();
+    for &item in &items {
+        if let Some(family) = families.family(item) {
+            variants.entry(family).or_default().push(item);
+        }
+    }
     let mut page = String::with_capacity(250_000);
     add_item_header(&mut page);
     page.push_str("<h1>");
@@ -126,7 +352,145 @@ fn render_filtered_index<F: FnMut(&Equipment) -> bool>(title: &str, elements: &[
     page.push_str("</h1><hr><br/><br/>");
     page.push_str("<table class=\"overview\">");
     page.push_str("<thead><tr><td>Name</td><td class=\"traitcolumn\">Traits</td><td>Value</td><td>Type</td><td>Source</td><td>Level</td></tr></thead>");
-    for (item, _) in elements.iter().filter(|(i, _)| filter(i)) {
+    for item in items {
+        let family = families.family(item).and_then(|f| Some((f, variants.remove(f)?)));
+        match family {
+            // Only one variant passed the filter, so it gets a normal row
+            Some((_, variants)) if variants.len() == 1 => render_index_row(&mut page, item),
+            Some((family, variants)) => {
+                let (first, last) = (variants[0], variants[variants.len() - 1]);
+                write!(
+                    page,
+                    "<tr><td><a href=\"{}\">{}</a> ({} variants)</td><td class=\"traitcolumn\">",
+                    family_url_name(family),
+                    family,
+                    variants.len()
+                );
+                render_traits_inline(&mut page, &first.traits);
+                write!(
+                    page,
+                    "</td><td>{}– {}</td><td>{}</td><td>{}</td><td>{}–{}</td></tr>",
+                    first.price,
+                    last.price,
+                    first.category(),
+                    first.source,
+                    first.level,
+                    last.level,
+                );
+            }
+            // Already part of a family row
+            None if families.family(item).is_some() => (),
+            None => render_index_row(&mut page, item),
+        }
+    }
+    page.push_str("</table>");
+    page
+}
+
+fn render_index_row(page: &mut String, item: &Equipment) {
+    write!(
+        page,
+        "<tr><td><a href=\"{}\">{}</a></td><td class=\"traitcolumn\">",
+        item.url_name(),
+        item.name,
+    );
+    render_traits_inline(page, &item.traits);
+    write!(
+        page,
+        "</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+        item.price,
+        item.category(),
+        item.source,
+        item.level,
+    );
+}
+
+/// One page per item family with what all variants have in common and a table of the differences.
+fn render_families(target: &str, elements: &[(Equipment, HtmlPage)], families: &ItemFamilies) -> io::Result<()> {
+    for family in families.names() {
+        let variants = elements
+            .iter()
+            .map(|(i, _)| i)
+            .filter(|i| families.family(i) == Some(family))
+            .sorted_by_key(|i| (i.level, i.price))
+            .collect_vec();
+        write_full_html_document(
+            &format!("{}/{}", target, family_url_name(family)),
+            family,
+            &render_family(family, &variants),
+        )?;
+    }
+    Ok(())
+}
+
+fn render_family(family: &str, variants: &[&Equipment]) -> String {
+    let paragraphs = variants
+        .iter()
+        .map(|v| PARAGRAPH_REGEX.find_iter(&v.description).map(|m| m.as_str()).collect_vec())
+        .collect_vec();
+    let shared = paragraphs
+        .first()
+        .map(|first| {
+            first
+                .iter()
+                .filter(|p| paragraphs.iter().all(|ps| ps.contains(p)))
+                .copied()
+                .collect_vec()
+        })
+        .unwrap_or_default();
+    let mut page = String::with_capacity(10_000);
+    add_item_header(&mut page);
+    write!(
+        page,
+        "<h1><a href=\"/item/{}\">{}</a><span class=\"type\">Item Family</span></h1><hr/>",
+        family_url_name(family),
+        family
+    );
+    page.push_str(&shared.join(""));
+    page.push_str("<table class=\"overview\">");
+    page.push_str(
+        "<thead><tr><td>Name</td><td>Level</td><td>Value</td><td>Bonus</td><td>DC</td><td class=\"traitcolumn\">Effect</td></tr></thead>",
+    );
+    for (variant, paragraphs) in variants.iter().zip(&paragraphs) {
+        let find = |regex: &Regex| regex.find(&variant.description).map(|m| m.as_str().to_string()).unwrap_or_default();
+        write!(
+            page,
+            "<tr><td><a href=\"/item/{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"traitcolumn\">{}</td></tr>",
+            variant.url_name(),
+            variant.name,
+            variant.level,
+            variant.price,
+            find(&BONUS_REGEX).split(' ').next().unwrap_or_default(),
+            find(&DC_REGEX),
+            paragraphs.iter().filter(|p| !shared.contains(p)).join("")
+        );
+    }
+    page.push_str("</table>");
+    page
+}
+
+fn render_weapon_index(elements: &[(Equipment, HtmlPage)]) -> String {
+    let mut page = String::with_capacity(100_000);
+    add_item_header(&mut page);
+    page.push_str(
+        "<h1>Weapons</h1><hr><a href=\"weapon_groups\">Weapon Groups</a><br/><br/>
+        <table class=\"overview\">
+        <thead><tr><td>Name</td><td class=\"traitcolumn\">Traits</td><td>Weapon Group</td><td>Damage</td><td>Value</td><td>Type</td><td>Range</td><td>Level</td></tr></thead>",
+    );
+    for (item, _) in elements
+        .iter()
+        .filter(|(i, _)| i.item_type == ItemType::Weapon)
+        .sorted_by_key(|&(i, _)| {
+            let category = match i.category {
+                ProficiencyGroup::Unarmed => 0,
+                ProficiencyGroup::Simple => 1,
+                ProficiencyGroup::Martial => 2,
+                ProficiencyGroup::Advanced => 3,
+                e => unreachable!("Unexpected weapon proficiency group: {:?}", e),
+            };
+            (category, i.level, i.price)
+        })
+    {
         write!(
             page,
             "<tr><td><a href=\"{}\">{}</a></td><td class=\"traitcolumn\">",
@@ -136,10 +500,16 @@ fn render_filtered_index<F: FnMut(&Equipment) -> bool>(title: &str, elements: &[
         render_traits_inline(&mut page, &item.traits);
         write!(
             page,
-            "</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            "</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            if item.group == WeaponGroup::NotAWeapon {
+                String::new()
+            } else {
+                format!("<a href=\"{}\">{}</a>", group_url(item.group), item.group.as_ref())
+            },
+            item.damage.clone().map(|d| d.to_string()).unwrap_or_else(String::new),
             item.price,
-            item.category(),
-            item.source,
+            item.category.as_ref(),
+            weapon_range(item),
             item.level,
         );
     }
@@ -147,23 +517,26 @@ fn render_filtered_index<F: FnMut(&Equipment) -> bool>(title: &str, elements: &[
     page
 }
 
-fn render_weapon_index(elements: &[(Equipment, HtmlPage)]) -> String {
+fn render_armor_index(elements: &[(Equipment, HtmlPage)]) -> String {
     let mut page = String::with_capacity(100_000);
     add_item_header(&mut page);
     page.push_str(
-        "<h1>Weapons</h1><hr><br/><br/>
+        "<h1>Armor</h1><hr><br/><br/>
         <table class=\"overview\">
-        <thead><tr><td>Name</td><td class=\"traitcolumn\">Traits</td><td>Weapon Group</td><td>Damage</td><td>Value</td><td>Type</td><td>Range</td><td>Level</td></tr></thead>",
+        <thead><tr><td>Name</td><td class=\"traitcolumn\">Traits</td><td>Category</td><td>AC Bonus</td><td>Dex Cap</td><td>Strength</td><td>Check Penalty</td><td>Speed Penalty</td><td>Group</td><td>Bulk</td><td>Value</td><td>Level</td></tr></thead>",
     );
-    for (item, _) in elements
+    for (item, armor) in elements
         .iter()
-        .filter(|(i, _)| i.item_type == ItemType::Weapon)
-        .sorted_by_key(|&(i, _)| match i.category {
-            ProficiencyGroup::Unarmed => 0,
-            ProficiencyGroup::Simple => 1,
-            ProficiencyGroup::Martial => 2,
-            ProficiencyGroup::Advanced => 3,
-            e => unreachable!("Unexpected weapon proficiency group: {:?}", e),
+        .filter_map(|(i, _)| Some((i, i.armor.as_ref()?)))
+        .sorted_by_key(|(i, _)| {
+            let category = match i.category {
+                ProficiencyGroup::Unarmored => 0,
+                ProficiencyGroup::Light => 1,
+                ProficiencyGroup::Medium => 2,
+                ProficiencyGroup::Heavy => 3,
+                _ => 4,
+            };
+            (category, i.level, i.price)
         })
     {
         write!(
@@ -175,16 +548,20 @@ fn render_weapon_index(elements: &[(Equipment, HtmlPage)]) -> String {
         render_traits_inline(&mut page, &item.traits);
         write!(
             page,
-            "</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
-            item.group.as_ref(),
-            item.damage.clone().map(|d| d.to_string()).unwrap_or_else(String::new),
-            item.price,
+            "</td><td>{}</td><td>{:+}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
             item.category.as_ref(),
-            if item.range == 0 {
-                "Melee".to_string()
+            armor.ac_bonus,
+            dex_cap(armor),
+            strength(armor),
+            penalty(armor.check_penalty),
+            speed_penalty(armor.speed_penalty),
+            if item.group == WeaponGroup::NotAWeapon {
+                ""
             } else {
-                format!("{} feet", item.range)
+                item.group.as_ref()
             },
+            item.weight,
+            item.price,
             item.level,
         );
     }
@@ -192,6 +569,127 @@ fn render_weapon_index(elements: &[(Equipment, HtmlPage)]) -> String {
     page
 }
 
+fn render_shield_index(elements: &[(Equipment, HtmlPage)]) -> String {
+    let mut page = String::with_capacity(50_000);
+    add_item_header(&mut page);
+    page.push_str(
+        "<h1>Shields</h1><hr><br/><br/>
+        <table class=\"overview\">
+        <thead><tr><td>Name</td><td class=\"traitcolumn\">Traits</td><td>AC Bonus</td><td>Hardness</td><td>HP (BT)</td><td>Speed Penalty</td><td>Bulk</td><td>Value</td><td>Level</td></tr></thead>",
+    );
+    for (item, shield) in elements
+        .iter()
+        .filter_map(|(i, _)| Some((i, i.shield.as_ref()?)))
+        .sorted_by_key(|(i, _)| (i.level, i.price))
+    {
+        write!(
+            page,
+            "<tr><td><a href=\"{}\">{}</a></td><td class=\"traitcolumn\">",
+            item.url_name(),
+            item.name,
+        );
+        render_traits_inline(&mut page, &item.traits);
+        write!(
+            page,
+            "</td><td>{:+}</td><td>{}</td><td>{} ({})</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            shield.ac_bonus,
+            item.hardness,
+            item.max_hp,
+            item.max_hp / 2,
+            speed_penalty(shield.speed_penalty),
+            item.weight,
+            item.price,
+            item.level,
+        );
+    }
+    page.push_str("</table>");
+    page
+}
+
+/// Items that cost at least this many times more or less than the median of their type and level
+const OUTLIER_FACTOR: u32 = 5;
+
+struct PriceOutlier<'a> {
+    item: &'a Equipment,
+    median: Price,
+}
+
+/// Levels with only a handful of items of one type don’t have a meaningful median and are skipped.
+fn price_outliers<'a>(items: impl Iterator<Item = &'a Equipment>) -> Vec<PriceOutlier<'a>> {
+    let mut groups: BTreeMap<(i32, &str), Vec<&Equipment>> = BTreeMap::new();
+    for item in items.filter(|i| i.price != Price::default()) {
+        groups.entry((item.level, item.item_type.as_ref())).or_default().push(item);
+    }
+    groups
+        .into_values()
+        .filter(|items| items.len() >= 5)
+        .flat_map(|items| {
+            let median = items.iter().map(|i| i.price).sorted().nth(items.len() / 2).unwrap_or_default();
+            items
+                .into_iter()
+                .filter(move |i| {
+                    let (price, median) = (i.price.as_copper(), median.as_copper());
+                    price >= median * OUTLIER_FACTOR || price * OUTLIER_FACTOR <= median
+                })
+                .map(move |item| PriceOutlier { item, median })
+        })
+        .collect()
+}
+
+fn render_price_outliers(elements: &[(Equipment, HtmlPage)]) -> String {
+    let mut page = String::with_capacity(20_000);
+    add_item_header(&mut page);
+    write!(
+        page,
+        "<h1>Price Outliers</h1><hr><p>Items that cost at least {}&#215; more or less than most items of the same type and level.</p>",
+        OUTLIER_FACTOR
+    );
+    page.push_str("<table class=\"overview\">");
+    page.push_str("<thead><tr><td>Name</td><td>Type</td><td>Level</td><td>Value</td><td>Median Value</td><td>Factor</td></tr></thead>");
+    for PriceOutlier { item, median } in price_outliers(elements.iter().map(|(i, _)| i)) {
+        write!(
+            page,
+            "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>&#215;{:.1}</td></tr>",
+            item.url_name(),
+            item.name,
+            item.item_type.as_ref(),
+            item.level,
+            item.price,
+            median,
+            item.price.as_copper() as f64 / median.as_copper() as f64,
+        );
+    }
+    page.push_str("</table>");
+    page
+}
+
+/// A flat list of all items for tools that filter by level, type, rarity or value.
+#[derive(Serialize, Debug)]
+struct ItemExport<'a> {
+    name: &'a str,
+    url: String,
+    item_type: ItemType,
+    level: i32,
+    rarity: Rarity,
+    price: String,
+    price_copper: u32,
+}
+
+fn item_export(elements: &[(Equipment, HtmlPage)]) -> Vec<ItemExport<'_>> {
+    elements
+        .iter()
+        .map(|(item, _)| ItemExport {
+            name: &item.name,
+            url: format!("/item/{}", item.url_name()),
+            item_type: item.item_type,
+            level: item.level,
+            rarity: item.traits.rarity,
+            price: item.price.to_string().trim_end().to_string(),
+            price_copper: item.price.as_copper(),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,14 +698,154 @@ mod tests {
     #[test]
     fn test_item_template() {
         let blackaxe: Equipment = serde_json::from_str(&read_test_file("equipment.db/blackaxe.json")).expect("Deserialization failed");
-        assert_eq_ignore_linebreaks(&blackaxe.render(&TRANSLATIONS), include_str!("../../tests/html/blackaxe.html"));
+        assert_eq_ignore_linebreaks(
+            &blackaxe.render((&TRANSLATIONS, &Grants::default(), &ItemFamilies::default())),
+            include_str!("../../tests/html/blackaxe.html"),
+        );
     }
 
     #[test]
     fn test_item_with_splash() {
         let bomb: Equipment =
             serde_json::from_str(&read_test_file("equipment.db/necrotic-bomb-major.json")).expect("Deserialization failed");
-        assert_eq_ignore_linebreaks(&bomb.render(&TRANSLATIONS), include_str!("../../tests/html/necrotic_bomb.html"));
+        assert_eq_ignore_linebreaks(
+            &bomb.render((&TRANSLATIONS, &Grants::default(), &ItemFamilies::default())),
+            include_str!("../../tests/html/necrotic_bomb.html"),
+        );
+    }
+
+    #[test]
+    fn should_render_armor_stats() {
+        let armor = ArmorStats {
+            ac_bonus: 4,
+            dex_cap: Some(1),
+            check_penalty: -2,
+            speed_penalty: -5,
+            strength: Some(16),
+        };
+        let mut page = String::new();
+        render_armor_stats(&mut page, &armor, WeaponGroup::Chain);
+        assert_eq!(
+            page,
+            "<b>AC Bonus</b> +4; <b>Dex Cap</b> +1; <b>Strength</b> 16<br/>\
+            <b>Check Penalty</b> -2; <b>Speed Penalty</b> -5 feet<br/>\
+            <b>Group</b> Chain<br/>"
+        );
+        let clothing = ArmorStats {
+            dex_cap: None,
+            ..Default::default()
+        };
+        page.clear();
+        render_armor_stats(&mut page, &clothing, WeaponGroup::NotAWeapon);
+        assert_eq!(
+            page,
+            "<b>AC Bonus</b> +0; <b>Dex Cap</b> —; <b>Strength</b> —<br/><b>Check Penalty</b> —; <b>Speed Penalty</b> —<br/>"
+        );
+    }
+
+    #[test]
+    fn should_render_weapon_group_usage_and_reload() {
+        let crossbow: Equipment = serde_json::from_str(
+            r#"{
+                "name": "Heavy Crossbow",
+                "type": "weapon",
+                "system": {
+                    "description": { "value": "" },
+                    "source": { "value": "" },
+                    "traits": { "value": [] },
+                    "category": "simple",
+                    "group": "bow",
+                    "range": 120,
+                    "reload": { "value": "2" },
+                    "usage": { "value": "held-in-two-hands" },
+                    "damage": { "damageType": "piercing", "dice": 1, "die": "d10" }
+                }
+            }"#,
+        )
+        .expect("Deserialization failed");
+        let mut page = String::new();
+        render_weapon_stats(&mut page, &crossbow);
+        assert_eq!(
+            page,
+            "<b>Group</b> <a href=\"/item/group_bow\">Bow</a><br/><b>Hands</b> 2<br/><b>Reload</b> 2<br/><b>Ammunition</b> Bolts<br/>"
+        );
+        assert!(crossbow
+            .critical_specialization()
+            .unwrap_or_default()
+            .starts_with("If the target of the critical hit is adjacent to a surface"));
+    }
+
+    fn potion(name: &str, level: i32, gp: u32, description: &str) -> (Equipment, HtmlPage) {
+        let extra = serde_json::json!({ "description": { "value": description } });
+        (
+            crate::tests::equipment(name, "consumable", level, gp, "common", &[], extra),
+            HtmlPage::default(),
+        )
+    }
+
+    #[test]
+    fn should_compare_family_variants() {
+        let items = [
+            potion("Elixir (Lesser)", 1, 4, "<p>You drink it.</p><p>You gain a +1 item bonus.</p>"),
+            potion(
+                "Elixir (Greater)",
+                10,
+                150,
+                "<p>You drink it.</p><p>You gain a +2 item bonus. DC 25.</p>",
+            ),
+            potion("Rope", 0, 1, "<p>Rope.</p>"),
+        ];
+        let families = ItemFamilies::new(items.iter().map(|(i, _)| i));
+        let variants = items.iter().map(|(i, _)| i).filter(|i| families.family(i).is_some()).collect_vec();
+        assert_eq_ignore_linebreaks(
+            &render_family("Elixir", &variants),
+            &format!(
+                "{}<h1><a href=\"/item/family_elixir\">Elixir</a><span class=\"type\">Item Family</span></h1><hr/>\
+                <p>You drink it.</p><table class=\"overview\">\
+                <thead><tr><td>Name</td><td>Level</td><td>Value</td><td>Bonus</td><td>DC</td><td class=\"traitcolumn\">Effect</td></tr></thead>\
+                <tr><td><a href=\"/item/elixir_lesser\">Elixir (Lesser)</a></td><td>1</td><td>4 gp </td><td>+1</td><td></td>\
+                <td class=\"traitcolumn\"><p>You gain a +1 item bonus.</p></td></tr>\
+                <tr><td><a href=\"/item/elixir_greater\">Elixir (Greater)</a></td><td>10</td><td>150 gp </td><td>+2</td><td>DC 25</td>\
+                <td class=\"traitcolumn\"><p>You gain a +2 item bonus. DC 25.</p></td></tr></table>",
+                {
+                    let mut header = String::new();
+                    add_item_header(&mut header);
+                    header
+                }
+            ),
+        );
+        let index = render_filtered_index("Consumable", &items, &families, |_| true);
+        assert!(index.contains("<a href=\"family_elixir\">Elixir</a> (2 variants)"));
+        assert!(index.contains("<td>4 gp – 150 gp </td>"));
+        assert!(!index.contains("elixir_greater"));
+        assert!(index.contains("<a href=\"rope\">Rope</a>"));
+    }
+
+    #[test]
+    fn should_index_free_items_by_price() {
+        assert_eq!(potion("Elixir", 1, 0, "").0.price_copper(), Some(0));
+        assert_eq!(potion("Elixir", 1, 4, "").0.price_copper(), Some(400));
+    }
+
+    #[test]
+    fn should_find_price_outliers() {
+        let items = [
+            potion("Elixir A", 3, 10, ""),
+            potion("Elixir B", 3, 12, ""),
+            potion("Elixir C", 3, 8, ""),
+            potion("Elixir D", 3, 11, ""),
+            potion("Elixir E", 3, 60, ""),
+            potion("Elixir F", 3, 1, ""),
+            potion("Elixir G", 4, 500, ""),
+        ];
+        let outliers = price_outliers(items.iter().map(|(i, _)| i));
+        assert_eq!(
+            outliers.iter().map(|o| o.item.name.as_str()).collect_vec(),
+            vec!["Elixir E", "Elixir F"]
+        );
+        assert_eq!(outliers[0].median.as_copper(), 1100);
+        let page = render_price_outliers(&items);
+        assert!(page.contains("<td>60 gp </td><td>11 gp </td><td>&#215;5.5</td>"));
     }
 
     #[test]
@@ -215,7 +853,7 @@ mod tests {
         let shield: Equipment =
             serde_json::from_str(&read_test_file("equipment.db/shield-of-the-unified-legion.json")).expect("Deserialization failed");
         assert_eq_ignore_linebreaks(
-            &shield.render(&TRANSLATIONS),
+            &shield.render((&TRANSLATIONS, &Grants::default(), &ItemFamilies::default())),
             include_str!("../../tests/html/shield_of_unified_legion.html"),
         );
     }
@@ -1,163 +1,151 @@
 use crate::{
-    data::{feats::Feat, traits::Translations, HasName},
-    html::{inline_rarity_if_not_common, render_trait_legend, render_traits, write_full_html_document, HtmlPage, Template},
+    data::{
+        ancestries::Ancestry, classes::Class, feat_type::FeatType, feats::Feat, grants::Grants, heritages::Heritage, outcomes::Outcomes,
+        prerequisites::PrerequisiteGraph, skills::Skill, traits::Translations, HasName, ObjectName,
+    },
+    html::{
+        actions::render_action_description, inline_rarity_if_not_common, render_grants, render_outcomes, render_rule_summary,
+        render_trait_legend, render_traits, write_full_html_document, HtmlPage, Template,
+    },
 };
 use itertools::Itertools;
 use lazy_static::lazy_static;
-use std::{borrow::Cow, fmt::Write, io};
-
-// TODO: automate getting these
-// For now, run
-// ls -1 | sed -E 's/([a-z])([a-z]+)\.json/"\U\1\L\2",/'
-// in the respective directories.
-const CLASSES: &[&str] = &[
-    "Alchemist",
-    "Barbarian",
-    "Bard",
-    "Champion",
-    "Cleric",
-    "Druid",
-    "Fighter",
-    "Gunslinger",
-    "Inventor",
-    "Investigator",
-    "Magus",
-    "Monk",
-    "Oracle",
-    "Psychic",
-    "Ranger",
-    "Rogue",
-    "Sorcerer",
-    "Summoner",
-    "Swashbuckler",
-    "Thaumaturge",
-    "Witch",
-    "Wizard",
-];
-
-const SKILLS: &[&str] = &[
-    "Acrobatics",
-    "Arcana",
-    "Athletics",
-    "Crafting",
-    "Deception",
-    "Diplomacy",
-    "Intimidation",
-    "Lore",
-    "Medicine",
-    "Nature",
-    "Occultism",
-    "Performance",
-    "Religion",
-    "Society",
-    "Stealth",
-    "Survival",
-    "Thievery",
-];
-
-const ANCESTRIES: &[&str] = &[
-    "Aasimar",
-    "Anadi",
-    "Android",
-    "Aphorite",
-    "Automaton",
-    "Azarketi",
-    "Beastkin",
-    "Catfolk",
-    "Changeling",
-    "Conrasu",
-    "Dhampir",
-    "Duskwalker",
-    "Dwarf",
-    "Elf",
-    "Fetchling",
-    "Fleshwarp",
-    "Ganzi",
-    "Gnoll",
-    "Gnome",
-    "Goblin",
-    "Goloma",
-    "Grippli",
-    "Half-Elf",
-    "Half-Orc",
-    "Halfling",
-    "Hobgoblin",
-    "Human",
-    "Ifrit",
-    "Kitsune",
-    "Kobold",
-    "Leshy",
-    "Lizardfolk",
-    "Orc",
-    "Oread",
-    "Pixie",
-    "Poppet",
-    "Ratfolk",
-    "Reflection",
-    "Shisk",
-    "Shoony",
-    "Skeleton",
-    "Sprite",
-    "Strix",
-    "Suli",
-    "Sylph",
-    "Tengu",
-    "Tiefling",
-    "Undine",
-];
-
-impl Template<&Translations> for Feat {
-    fn render(&self, trait_descriptions: &Translations) -> Cow<'_, str> {
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    io,
+};
+use strum::IntoEnumIterator;
+
+/// Everything a feat page needs besides the feat itself.
+type FeatData<'a> = (&'a Translations, &'a Grants, &'a PrerequisiteGraph, &'a FeatLists);
+
+/// The classes, ancestries and skills that get their own feat lists, derived from the loaded data.
+pub struct FeatLists {
+    classes: Vec<String>,
+    ancestries: Vec<String>,
+    skills: Vec<String>,
+    // Selection header with nothing highlighted or expanded
+    header: String,
+}
+
+impl FeatLists {
+    /// Some heritages (e.g. Half-Elf or versatile heritages like Aasimar) have their own feats.
+    /// Those are listed alongside the ancestries if any feat has their trait.
+    pub fn new(classes: &[Class], ancestries: &[Ancestry], heritages: &[Heritage], feats: &[Feat]) -> Self {
+        let feat_traits: HashSet<&str> = feats.iter().flat_map(|f| f.traits.misc.iter()).map(String::as_str).collect();
+        let ancestries = ancestries
+            .iter()
+            .map(|a| a.name.clone())
+            .chain(
+                heritages
+                    .iter()
+                    .map(|h| h.name.clone())
+                    .filter(|h| feat_traits.contains(h.to_lowercase().as_str())),
+            )
+            .sorted()
+            .dedup()
+            .collect();
+        FeatLists::from_names(classes.iter().map(|c| c.name.clone()).collect(), ancestries)
+    }
+
+    fn from_names(classes: Vec<String>, ancestries: Vec<String>) -> Self {
+        let mut lists = FeatLists {
+            classes,
+            ancestries,
+            skills: Skill::iter().map(|s| s.as_ref().to_string()).sorted().collect(),
+            header: String::new(),
+        };
+        let mut header = String::with_capacity(3000);
+        render_selection_header(&mut header, &lists, FeatListType::Unknown, None);
+        lists.header = header;
+        lists
+    }
+
+    pub fn classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    pub fn ancestries(&self) -> &[String] {
+        &self.ancestries
+    }
+
+    /// Class and ancestry feats that don’t have the trait of any known class or ancestry and therefore won’t show up in any list.
+    pub fn unmatched_feats<'a>(&'a self, feats: &'a [Feat]) -> impl Iterator<Item = &'a Feat> {
+        feats.iter().filter(|f| !f.traits.misc.contains(&ARCHETYPE_TRAIT)).filter(move |f| {
+            let known = match f.feat_type {
+                FeatType::Class => &self.classes,
+                FeatType::Ancestry => &self.ancestries,
+                _ => return false,
+            };
+            !known.iter().any(|k| f.traits.misc.contains(&k.to_lowercase()))
+        })
+    }
+}
+
+impl Template<FeatData<'_>> for Feat {
+    fn render(&self, (trait_descriptions, grants, prerequisites, _): FeatData) -> Cow<'_, str> {
         let mut page = String::with_capacity(50000);
-        render_single_feat(&mut page, trait_descriptions, self);
+        render_single_feat(&mut page, trait_descriptions, grants, prerequisites, self);
         Cow::Owned(page)
     }
 
-    fn header(&self) -> Option<Cow<'_, str>> {
-        Some(Cow::Borrowed(&STATIC_SELECTION_FEAT_HEADER))
+    fn header(&self, (_, _, _, lists): FeatData) -> Option<Cow<'_, str>> {
+        Some(Cow::Owned(lists.header.clone()))
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], (_, _, _, lists): FeatData) -> String {
         let feats = elements.iter().filter(|(f, _)| f.level != 0).collect_vec();
-        render_full_feat_list(&feats)
+        render_full_feat_list(&feats, lists)
     }
 
     fn category(&self) -> Cow<'_, str> {
         Cow::Borrowed("Feat")
     }
 
-    fn render_subindices(target: &str, elements: &[(Self, HtmlPage)]) -> io::Result<()> {
+    fn outcomes(&self) -> Option<&Outcomes> {
+        Some(&self.outcomes)
+    }
+
+    fn render_subindices(target: &str, elements: &[(Self, HtmlPage)], (_, _, _, lists): FeatData) -> io::Result<()> {
         let feats = elements.iter().filter(|(f, _)| f.level != 0).collect_vec();
-        for class in CLASSES {
+        for class in &lists.classes {
             write_full_html_document(
                 &format!("{}/{}_index", target, class.to_lowercase()),
                 &format!("{} Feats", class),
-                &render_filtered_feat_list(&feats, class, FeatListType::Class),
+                &render_filtered_feat_list(&feats, lists, class, FeatListType::Class),
             )?;
         }
-        for ancestry in ANCESTRIES {
+        for ancestry in &lists.ancestries {
             write_full_html_document(
                 &format!("{}/{}_index", target, ancestry.to_lowercase()),
                 &format!("{} Feats", ancestry),
-                &render_filtered_feat_list(&feats, ancestry, FeatListType::Ancestry),
+                &render_filtered_feat_list(&feats, lists, ancestry, FeatListType::Ancestry),
             )?;
         }
-        for skill in SKILLS {
+        for skill in &lists.skills {
             write_full_html_document(
                 &format!("{}/{}_index", target, skill.to_lowercase()),
                 &format!("{} Feats", skill),
-                &render_skill_feat_list(&feats, skill),
+                &render_skill_feat_list(&feats, lists, skill),
             )?;
         }
         write_full_html_document(
             &format!("{}/general_index", target),
             "General Feats",
-            &render_general_feat_list(&feats),
+            &render_general_feat_list(&feats, lists),
         )
     }
 }
 
-fn render_single_feat(page: &mut String, trait_descriptions: &Translations, feat: &Feat) {
+fn render_single_feat(
+    page: &mut String,
+    trait_descriptions: &Translations,
+    grants: &Grants,
+    prerequisites: &PrerequisiteGraph,
+    feat: &Feat,
+) {
     write!(
         page,
         "<h1><a href=\"/feat/{}\">{}</a> {}<span class=\"type\">Feat {}</span></h1><hr/>",
@@ -176,18 +164,128 @@ fn render_single_feat(page: &mut String, trait_descriptions: &Translations, feat
     }
     if !feat.prerequisites.is_empty() {
         page.push_str("<b>Prerequisites</b> ");
-        page.push_str(&feat.prerequisites.join(", "));
+        let links = feat
+            .prerequisites
+            .iter()
+            .map(|p| match prerequisites.resolve(p) {
+                Some(target) => format!("<a href=\"/{}/{}\">{}</a>", target.category, target.url_name(), p.text),
+                None => p.text.clone(),
+            })
+            .join(", ");
+        page.push_str(&links);
     }
     if !feat.source.is_empty() || !feat.prerequisites.is_empty() {
         page.push_str("<hr/>");
     }
-    page.push_str(&feat.description);
+    render_action_description(page, &render_outcomes(&feat.description, &feat.outcomes), &feat.block);
+    render_rule_summary(page, &feat.rules);
+    render_grants(page, grants, "feat", feat);
+    let required_by = prerequisites.required_by(feat);
+    if !required_by.is_empty() {
+        page.push_str("<h2>Feats that require this</h2><ul>");
+        for name in required_by {
+            write!(page, "<li><a href=\"/feat/{}\">{}</a></li>", ObjectName(name).url_name(), name);
+        }
+        page.push_str("</ul>");
+    }
     page.push_str("<hr/>");
     render_trait_legend(page, &feat.traits, trait_descriptions);
 }
 
-fn render_full_feat_list(feats: &[&(Feat, HtmlPage)]) -> String {
-    let mut page = render_feat_list_header(None, FeatListType::Unknown, None);
+/// Lists feats grouped by level, skipping automatically granted ones. The feats have to be sorted by level already.
+pub fn render_feats_by_level<'a>(page: &mut String, feats: impl Iterator<Item = &'a Feat>) {
+    for (level, feats) in &feats.filter(|f| f.level != 0).group_by(|f| f.level) {
+        write!(page, "<h3>Level {}</h3><ul>", level);
+        for feat in feats {
+            write!(
+                page,
+                "<li><a href=\"/feat/{}\">{}</a> {}{}</li>",
+                feat.url_name(),
+                feat.name,
+                feat.action_type.img(&feat.actions),
+                inline_rarity_if_not_common(&feat.traits.rarity)
+            );
+        }
+        page.push_str("</ul>");
+    }
+}
+
+/// Renders one page per class with all chains of class feats that require other class feats.
+pub fn render_feat_trees(target: &str, feats: &[(Feat, HtmlPage)], prerequisites: &PrerequisiteGraph, lists: &FeatLists) -> io::Result<()> {
+    for class in &lists.classes {
+        let class_trait = class.to_lowercase();
+        let class_feats: HashMap<_, _> = feats
+            .iter()
+            .map(|(f, _)| f)
+            .filter(|f| f.level != 0 && f.traits.misc.contains(&class_trait))
+            .map(|f| (f.url_name(), f))
+            .collect();
+        let children = |feat: &Feat| {
+            prerequisites
+                .required_by(feat)
+                .iter()
+                .filter_map(|name| class_feats.get(&ObjectName(name).url_name()).copied())
+                .sorted_by_key(|f| (f.level, &f.name))
+                .collect_vec()
+        };
+        let roots = class_feats
+            .values()
+            .copied()
+            .filter(|f| {
+                !prerequisites
+                    .required_feats(f)
+                    .iter()
+                    .any(|r| class_feats.contains_key(&r.url_name()))
+            })
+            .filter(|f| !children(f).is_empty())
+            .sorted_by_key(|f| (f.level, &f.name))
+            .collect_vec();
+        let mut page = String::with_capacity(20_000);
+        write!(
+            page,
+            "<h1>{} Feat Chains</h1><hr/><p>Class feats that are prerequisites for other class feats. See <a href=\"/feat/{}_index\">all {} feats</a>.</p>",
+            class, class_trait, class
+        );
+        page.push_str("<ul class=\"feat-tree\">");
+        for root in roots {
+            render_feat_tree_node(&mut page, root, &children, &mut vec![]);
+        }
+        page.push_str("</ul>");
+        write_full_html_document(
+            &format!("{}/{}_tree", target, class_trait),
+            &format!("{} Feat Chains", class),
+            &page,
+        )?;
+    }
+    Ok(())
+}
+
+fn render_feat_tree_node<'a>(page: &mut String, feat: &'a Feat, children: &dyn Fn(&Feat) -> Vec<&'a Feat>, path: &mut Vec<&'a str>) {
+    write!(
+        page,
+        "<li><a href=\"/feat/{}\">{}</a> <span class=\"lvl\">{}</span>",
+        feat.url_name(),
+        feat.name,
+        feat.level
+    );
+    // Guard against broken data with circular prerequisites
+    if !path.contains(&feat.name.as_str()) {
+        let next = children(feat);
+        if !next.is_empty() {
+            path.push(&feat.name);
+            page.push_str("<ul>");
+            for child in next {
+                render_feat_tree_node(page, child, children, path);
+            }
+            page.push_str("</ul>");
+            path.pop();
+        }
+    }
+    page.push_str("</li>");
+}
+
+fn render_full_feat_list(feats: &[&(Feat, HtmlPage)], lists: &FeatLists) -> String {
+    let mut page = render_feat_list_header(lists, None, FeatListType::Unknown, None);
     page.push_str("<table class=\"overview\">");
     page.push_str("<thead><tr><td>Name</td><td>Level</td></tr></thead>");
     for (feat, _) in feats {
@@ -224,17 +322,20 @@ fn render_feat_row(s: &mut String, feat: &Feat, page: &HtmlPage) {
     );
 }
 
-fn render_filtered_feat_list(feats: &[&(Feat, HtmlPage)], filter_trait: &str, list_type: FeatListType) -> String {
-    let mut page = render_feat_list_header(Some(filter_trait), list_type, Some(filter_trait));
+fn render_filtered_feat_list(feats: &[&(Feat, HtmlPage)], lists: &FeatLists, filter_trait: &str, list_type: FeatListType) -> String {
     let trait_lower = filter_trait.to_lowercase();
+    let mut page = render_feat_list_header(lists, Some(filter_trait), list_type, Some(filter_trait));
+    if list_type == FeatListType::Class {
+        write!(page, "<p><a href=\"{}_tree\">Feat chains</a></p>", trait_lower);
+    }
     for (feat, p) in feats.iter().filter(|(f, _)| f.traits.misc.contains(&trait_lower)) {
         render_feat_row(&mut page, feat, p);
     }
     page
 }
 
-fn render_general_feat_list(feats: &[&(Feat, HtmlPage)]) -> String {
-    let mut page = render_feat_list_header(Some("General"), FeatListType::Unknown, None);
+fn render_general_feat_list(feats: &[&(Feat, HtmlPage)], lists: &FeatLists) -> String {
+    let mut page = render_feat_list_header(lists, Some("General"), FeatListType::Unknown, None);
     feats
         .iter()
         .filter(|(f, _)| f.traits.misc.contains(&GENERAL_TRAIT))
@@ -245,21 +346,23 @@ fn render_general_feat_list(feats: &[&(Feat, HtmlPage)]) -> String {
     page
 }
 
-fn render_skill_feat_list(feats: &[&(Feat, HtmlPage)], skill: &str) -> String {
+/// Skill feats that list the skill in their prerequisites, excluding archetype feats.
+pub(crate) fn is_skill_feat_for(feat: &Feat, skill: &str) -> bool {
     let skill_lower = skill.to_lowercase();
-    let mut page = render_feat_list_header(Some(skill), FeatListType::Skill, Some(skill));
-    feats
-        .iter()
-        .filter(|(f, _)| f.traits.misc.contains(&SKILL_TRAIT))
-        .filter(|(f, _)| !f.traits.misc.contains(&ARCHETYPE_TRAIT))
-        .filter(|(f, _)| f.prerequisites.iter().any(|p| p.to_lowercase().contains(&skill_lower)))
-        .for_each(|(feat, p)| {
-            render_feat_row(&mut page, feat, p);
-        });
+    feat.traits.misc.contains(&SKILL_TRAIT)
+        && !feat.traits.misc.contains(&ARCHETYPE_TRAIT)
+        && feat.prerequisites.iter().any(|p| p.text.to_lowercase().contains(&skill_lower))
+}
+
+fn render_skill_feat_list(feats: &[&(Feat, HtmlPage)], lists: &FeatLists, skill: &str) -> String {
+    let mut page = render_feat_list_header(lists, Some(skill), FeatListType::Skill, Some(skill));
+    feats.iter().filter(|(f, _)| is_skill_feat_for(f, skill)).for_each(|(feat, p)| {
+        render_feat_row(&mut page, feat, p);
+    });
     page
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 enum FeatListType {
     // General,
     Skill,
@@ -300,7 +403,7 @@ const ANCESTRY_FEAT_HEADER_LABELS: &str = r#"
 <label for="cl-Ancestrylist" class="lt pseudolink">Filter by Ancestry</label>
 </div>
 "#;
-fn collapsible_toc(header: &mut String, list: &[&str], list_name: &str, expanded: bool, highlighted: Option<&str>) {
+fn collapsible_toc(header: &mut String, list: &[String], list_name: &str, expanded: bool, highlighted: Option<&str>) {
     write!(
         header,
         r#"
@@ -315,7 +418,7 @@ fn collapsible_toc(header: &mut String, list: &[&str], list_name: &str, expanded
             header,
             r#"<a href="{}_index"{}>{} </a>"#,
             e.to_lowercase(),
-            if expanded && Some(e) == highlighted.as_ref() {
+            if expanded && Some(e.as_str()) == highlighted {
                 ""
             } else {
                 " class=\"hoverlink\""
@@ -326,36 +429,36 @@ fn collapsible_toc(header: &mut String, list: &[&str], list_name: &str, expanded
     header.push_str("</div>");
 }
 
-fn render_selection_header(header: &mut String, list_type: FeatListType, highlighted: Option<&str>) {
+fn render_selection_header(header: &mut String, lists: &FeatLists, list_type: FeatListType, highlighted: Option<&str>) {
     header.push_str(match list_type {
         FeatListType::Skill => SKILL_FEAT_HEADER_LABELS,
         FeatListType::Class => CLASS_FEAT_HEADER_LABELS,
         FeatListType::Ancestry => ANCESTRY_FEAT_HEADER_LABELS,
         FeatListType::Unknown => HEADER_LABELS,
     });
-    collapsible_toc(header, CLASSES, "Class", list_type == FeatListType::Class, highlighted);
-    collapsible_toc(header, SKILLS, "Skill", list_type == FeatListType::Skill, highlighted);
-    collapsible_toc(header, ANCESTRIES, "Ancestry", list_type == FeatListType::Ancestry, highlighted);
+    collapsible_toc(header, &lists.classes, "Class", list_type == FeatListType::Class, highlighted);
+    collapsible_toc(header, &lists.skills, "Skill", list_type == FeatListType::Skill, highlighted);
+    collapsible_toc(
+        header,
+        &lists.ancestries,
+        "Ancestry",
+        list_type == FeatListType::Ancestry,
+        highlighted,
+    );
 }
 
 lazy_static! {
-    // Static header with nothing highlighted or expanded
-    static ref STATIC_SELECTION_FEAT_HEADER: String = {
-        let mut header = String::with_capacity(3000);
-        render_selection_header(&mut header, FeatListType::Unknown, None);
-        header
-    };
     static ref SKILL_TRAIT: String = String::from("skill");
     static ref GENERAL_TRAIT: String = String::from("general");
-    static ref ARCHETYPE_TRAIT: String = String::from("archetype");
+    pub(crate) static ref ARCHETYPE_TRAIT: String = String::from("archetype");
 }
 
-fn render_feat_list_header(category: Option<&str>, list_type: FeatListType, selection: Option<&str>) -> String {
+fn render_feat_list_header(lists: &FeatLists, category: Option<&str>, list_type: FeatListType, selection: Option<&str>) -> String {
     let mut page = String::with_capacity(50_000);
     if list_type == FeatListType::Unknown && selection.is_none() {
-        page.push_str(&STATIC_SELECTION_FEAT_HEADER);
+        page.push_str(&lists.header);
     } else {
-        render_selection_header(&mut page, list_type, selection);
+        render_selection_header(&mut page, lists, list_type, selection);
     }
     match category {
         Some(c) => {
@@ -371,21 +474,114 @@ fn render_feat_list_header(category: Option<&str>, list_type: FeatListType, sele
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::{assert_eq_ignore_linebreaks, read_test_file, TRANSLATIONS};
+    use crate::tests::{assert_eq_ignore_linebreaks, feat, read_test_file, TRANSLATIONS};
 
     #[test]
     fn test_feat_template() {
         let feat: Feat = serde_json::from_str(&read_test_file("feats.db/sever-space.json")).expect("Deserialization failed");
         let mut s = String::new();
-        render_single_feat(&mut s, &TRANSLATIONS, &feat);
+        render_single_feat(&mut s, &TRANSLATIONS, &Grants::default(), &PrerequisiteGraph::default(), &feat);
         assert_eq_ignore_linebreaks(&s, include_str!("../../tests/html/sever_space.html"));
     }
 
     #[test]
     fn test_render_feat_header() {
-        assert_eq_ignore_linebreaks(
-            &STATIC_SELECTION_FEAT_HEADER,
-            include_str!("../../tests/html/no_selection_feat_header.html"),
+        let lists = FeatLists::from_names(
+            [
+                "Alchemist",
+                "Barbarian",
+                "Bard",
+                "Champion",
+                "Cleric",
+                "Druid",
+                "Fighter",
+                "Gunslinger",
+                "Inventor",
+                "Investigator",
+                "Magus",
+                "Monk",
+                "Oracle",
+                "Psychic",
+                "Ranger",
+                "Rogue",
+                "Sorcerer",
+                "Summoner",
+                "Swashbuckler",
+                "Thaumaturge",
+                "Witch",
+                "Wizard",
+            ]
+            .map(String::from)
+            .to_vec(),
+            [
+                "Aasimar",
+                "Anadi",
+                "Android",
+                "Aphorite",
+                "Automaton",
+                "Azarketi",
+                "Beastkin",
+                "Catfolk",
+                "Changeling",
+                "Conrasu",
+                "Dhampir",
+                "Duskwalker",
+                "Dwarf",
+                "Elf",
+                "Fetchling",
+                "Fleshwarp",
+                "Ganzi",
+                "Gnoll",
+                "Gnome",
+                "Goblin",
+                "Goloma",
+                "Grippli",
+                "Half-Elf",
+                "Half-Orc",
+                "Halfling",
+                "Hobgoblin",
+                "Human",
+                "Ifrit",
+                "Kitsune",
+                "Kobold",
+                "Leshy",
+                "Lizardfolk",
+                "Orc",
+                "Oread",
+                "Pixie",
+                "Poppet",
+                "Ratfolk",
+                "Reflection",
+                "Shisk",
+                "Shoony",
+                "Skeleton",
+                "Sprite",
+                "Strix",
+                "Suli",
+                "Sylph",
+                "Tengu",
+                "Tiefling",
+                "Undine",
+            ]
+            .map(String::from)
+            .to_vec(),
+        );
+        assert_eq_ignore_linebreaks(&lists.header, include_str!("../../tests/html/no_selection_feat_header.html"));
+    }
+
+    #[test]
+    fn should_report_unmatched_feats() {
+        let lists = FeatLists::from_names(vec![String::from("Fighter")], vec![String::from("Half-Elf")]);
+        let feats = [
+            feat("Power Attack", FeatType::Class, 1, &["fighter"]),
+            feat("Elf Atavism", FeatType::Ancestry, 1, &["half-elf"]),
+            feat("Future Class Feat", FeatType::Class, 1, &["newclass"]),
+            feat("Fighter Dedication", FeatType::Class, 1, &["archetype", "dedication"]),
+            feat("Assurance", FeatType::Skill, 1, &["general", "skill"]),
+        ];
+        assert_eq!(
+            lists.unmatched_feats(&feats).map(|f| f.name.as_str()).collect_vec(),
+            vec!["Future Class Feat"]
         );
     }
 }
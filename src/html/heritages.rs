@@ -1,6 +1,6 @@
 use crate::{
-    data::{heritages::Heritage, traits::Rarity, HasName},
-    html::{ancestries::add_ancestry_feat_link, HtmlPage, Template},
+    data::{grants::Grants, heritages::Heritage, traits::Rarity, HasName},
+    html::{ancestries::add_ancestry_feat_link, render_grants, render_rule_summary, HtmlPage, Template},
 };
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -10,8 +10,8 @@ lazy_static! {
     static ref CURSIVE_FLAVOUR_TEXT: Regex = Regex::new("<em>(.*?)</em>").unwrap();
 }
 
-impl Template<()> for Heritage {
-    fn render(&self, _: ()) -> Cow<'_, str> {
+impl Template<&Grants> for Heritage {
+    fn render(&self, grants: &Grants) -> Cow<'_, str> {
         let mut page = format!(
             "<h1><a href=\"/heritage/{}\">{}</a></h1><hr/><b>Source </b>{}<br/>{}",
             self.url_name(),
@@ -19,6 +19,8 @@ impl Template<()> for Heritage {
             &self.source,
             &self.description
         );
+        render_rule_summary(&mut page, &self.rules);
+        render_grants(&mut page, grants, "heritage", self);
         add_ancestry_feat_link(&self.url_name(), self.name(), &mut page);
         Cow::Owned(page)
     }
@@ -27,7 +29,7 @@ impl Template<()> for Heritage {
         Cow::Borrowed("Heritage")
     }
 
-    fn render_index(elements: &[(Self, super::HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, super::HtmlPage)], _: &Grants) -> String {
         let mut index = String::with_capacity(10_000);
 
         index.push_str(HEADER);
@@ -41,7 +43,7 @@ impl Template<()> for Heritage {
         index
     }
 
-    fn header(&self) -> Option<Cow<'_, str>> {
+    fn header(&self, _: &Grants) -> Option<Cow<'_, str>> {
         Some(Cow::Borrowed(HEADER))
     }
 }
@@ -70,6 +72,6 @@ mod tests {
     #[test]
     fn ancestry_rendering_test() {
         let spooder: Heritage = serde_json::from_str(&read_test_file("heritages.db/aasimar.json")).expect("Deserialization failed");
-        assert_eq_ignore_linebreaks(&spooder.render(()), include_str!("../../tests/html/aasimar.html"));
+        assert_eq_ignore_linebreaks(&spooder.render(&Grants::default()), include_str!("../../tests/html/aasimar.html"));
     }
 }
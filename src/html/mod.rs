@@ -1,18 +1,25 @@
 use crate::{
     data::{
-        traits::{clean_trait_name, Rarity, Traits, Translations},
-        HasName,
+        grants::Grants,
+        outcomes::{outcome_paragraphs, Degree, Outcomes},
+        rules::Rule,
+        traits::{clean_trait_name, trait_url_name, Rarity, Traits, Translations},
+        HasName, ObjectName,
     },
     get_data_path, URL_REPLACEMENTS,
 };
 use convert_case::{Case, Casing};
 use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     borrow::Cow,
-    fmt, fs,
+    fmt::{self, Write as _},
+    fs,
     io::{self, BufReader, BufWriter, Write},
 };
+use strum::IntoEnumIterator;
 
 pub(crate) mod actions;
 pub(crate) mod ancestries;
@@ -21,12 +28,17 @@ pub(crate) mod backgrounds;
 pub(crate) mod classes;
 pub(crate) mod classfeatures;
 pub(crate) mod conditions;
+pub(crate) mod crafting;
 pub(crate) mod creatures;
 pub(crate) mod deities;
 pub(crate) mod equipment;
 pub(crate) mod feats;
 pub(crate) mod heritages;
+pub(crate) mod runes;
+pub(crate) mod skills;
+pub(crate) mod spell_items;
 pub(crate) mod spells;
+pub(crate) mod treasure;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Default)]
 pub(crate) struct HtmlPage {
@@ -34,6 +46,12 @@ pub(crate) struct HtmlPage {
     pub content: String,
     pub category: String,
     pub id: String,
+    /// Lets the search find what happens on e.g. a critical failure.
+    #[serde(flatten, default)]
+    pub outcomes: Outcomes,
+    /// Lets the search filter by value, e.g. items under 50 gp.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub price_copper: Option<u32>,
 }
 
 impl HasName for HtmlPage {
@@ -50,10 +68,10 @@ where
 
     fn category(&self) -> Cow<'_, str>;
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String;
+    fn render_index(elements: &[(Self, HtmlPage)], d: AdditionalData) -> String;
 
     // noop by default
-    fn render_subindices(_target: &str, _elements: &[(Self, HtmlPage)]) -> io::Result<()> {
+    fn render_subindices(_target: &str, _elements: &[(Self, HtmlPage)], _d: AdditionalData) -> io::Result<()> {
         Ok(())
     }
 
@@ -61,7 +79,15 @@ where
         URL_REPLACEMENTS.replace_all(&self.category(), "").to_string()
     }
 
-    fn header(&self) -> Option<Cow<'_, str>> {
+    fn header(&self, _d: AdditionalData) -> Option<Cow<'_, str>> {
+        None
+    }
+
+    fn outcomes(&self) -> Option<&Outcomes> {
+        None
+    }
+
+    fn price_copper(&self) -> Option<u32> {
         None
     }
 }
@@ -93,23 +119,37 @@ pub(crate) fn render<T: Template<Additional>, Additional: Copy, P: fmt::Display>
     target: &str,
     additional_data: Additional,
 ) -> io::Result<Vec<(T, HtmlPage)>> {
-    fs::create_dir_all(target)?;
+    render_loaded(read_compendium(folders)?, target, additional_data)
+}
+
+/// Reads and sorts all elements from the given folders without rendering them.
+/// Useful when other pages depend on the data before it can be rendered.
+pub(crate) fn read_compendium<T: DeserializeOwned + Ord, P: fmt::Display>(folders: &[P]) -> io::Result<Vec<T>> {
     let mut elements = folders.iter().map(read_data).flatten_ok().collect::<io::Result<Vec<T>>>()?;
     elements.sort();
+    Ok(elements)
+}
+
+pub(crate) fn render_loaded<T: Template<Additional>, Additional: Copy>(
+    elements: Vec<T>,
+    target: &str,
+    additional_data: Additional,
+) -> io::Result<Vec<(T, HtmlPage)>> {
+    fs::create_dir_all(target)?;
     let pages = elements
         .into_iter()
         .filter(|e| !e.name().starts_with("[Empty"))
         .map(|e| attach_html(e, additional_data))
         .filter(|(_, p)| !p.content.is_empty())
         .collect_vec();
-    Template::render_subindices(target, &pages)?;
+    Template::render_subindices(target, &pages, additional_data)?;
     write_full_html_document(
         &format!("{}/index.html", target),
         &format!("{} List", title_from_target_folder(target)),
-        &Template::render_index(&pages),
+        &Template::render_index(&pages, additional_data),
     )?;
     for (e, page) in &pages {
-        if let Some(header) = e.header() {
+        if let Some(header) = e.header(additional_data) {
             write_full_html_document_with_header(&format!("{}/{}", target, page.url_name()), e.name(), &page.content, &header)?;
         } else {
             write_full_html_document(&format!("{}/{}", target, page.url_name()), e.name(), &page.content)?;
@@ -124,6 +164,8 @@ pub(crate) fn attach_html<A, T: Template<A>>(e: T, additional_data: A) -> (T, Ht
         category: e.category().to_string(),
         id: format!("{}-{}", e.category_url_safe(), URL_REPLACEMENTS.replace_all(e.name(), "")),
         content: e.render(additional_data).to_string(),
+        outcomes: e.outcomes().cloned().unwrap_or_default(),
+        price_copper: e.price_copper(),
     };
     (e, page)
 }
@@ -158,6 +200,11 @@ fn rarity_if_not_common(page: &mut String, rarity: &Rarity) {
     }
 }
 
+lazy_static! {
+    /// The paragraphs of a description, used to find the text that variants of a family share
+    pub(crate) static ref PARAGRAPH_REGEX: Regex = Regex::new(r"(?s)<p>.*?</p>").unwrap();
+}
+
 // This is a zero-width space that allows browser to linewrap between these spans if necessary
 const ZERO_WIDTH_BREAKING_SPACE: char = '\u{200B}';
 
@@ -187,8 +234,8 @@ fn render_traits_in(page: &mut String, traits: &Traits, open_element: &str, clos
 fn render_misc_traits(traits: &Traits, page: &mut String) {
     let rarity_string = traits.rarity.as_ref().to_lowercase();
     for t in traits.misc.iter().filter(|t| t != &&rarity_string) {
-        page.push_str("<a href=\"trait_");
-        page.push_str(&t.to_lowercase());
+        page.push_str("<a href=\"/trait/");
+        page.push_str(&trait_url_name(t));
         page.push_str("\"><span class=\"trait\">");
         page.push_str(&t.to_case(Case::Pascal));
         page.push_str("</span></a>");
@@ -223,6 +270,84 @@ pub fn render_trait_legend(mut page: &mut String, traits: &Traits, trait_descrip
     page.push_str("</div>");
 }
 
+/// Links everything granted by or granting this element. Does nothing if there are none.
+pub fn render_grants<T: HasName>(page: &mut String, grants: &Grants, category: &'static str, element: &T) {
+    let granted = grants.grants(category, element);
+    if !granted.is_empty() {
+        page.push_str("<h2>Grants</h2><ul>");
+        for grant in granted {
+            match grant.category {
+                Some(category) => write!(
+                    page,
+                    "<li><a href=\"/{}/{}\">{}</a></li>",
+                    category,
+                    ObjectName(&grant.name).url_name(),
+                    grant.name
+                ),
+                None => write!(page, "<li>{}</li>", grant.name),
+            };
+        }
+        page.push_str("</ul>");
+    }
+    let granted_by = grants.granted_by(category, element);
+    if !granted_by.is_empty() {
+        page.push_str("<h2>Granted by</h2><ul>");
+        for source in granted_by {
+            write!(
+                page,
+                "<li><a href=\"/{}/{}\">{}</a></li>",
+                source.category,
+                source.url_name(),
+                source.name
+            );
+        }
+        page.push_str("</ul>");
+    }
+}
+
+/// Replaces the degree of success paragraphs in a description with one table,
+/// so all checks look the same no matter how the description was written.
+pub fn render_outcomes<'a>(description: &'a str, outcomes: &Outcomes) -> Cow<'a, str> {
+    let paragraphs = outcome_paragraphs(description);
+    if paragraphs.is_empty() {
+        return Cow::Borrowed(description);
+    }
+    let mut rendered = String::with_capacity(description.len() + 200);
+    let mut position = 0;
+    for (i, (_, range, _)) in paragraphs.into_iter().enumerate() {
+        rendered.push_str(&description[position..range.start]);
+        position = range.end;
+        if i == 0 {
+            rendered.push_str("<table class=\"outcomes\">");
+            for degree in Degree::iter() {
+                write!(
+                    rendered,
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    degree.as_ref(),
+                    outcomes.get(degree).unwrap_or("—")
+                );
+            }
+            rendered.push_str("</table>");
+        }
+    }
+    rendered.push_str(&description[position..]);
+    Cow::Owned(rendered)
+}
+
+/// Lists the effects of all rule elements we know how to describe. Does nothing if there are none.
+pub fn render_rule_summary(page: &mut String, rules: &[Rule]) {
+    let summaries = rules.iter().filter_map(Rule::summary).collect_vec();
+    if !summaries.is_empty() {
+        page.push_str("<h2>Mechanical Effects</h2><ul class=\"rules\">");
+        for summary in summaries {
+            page.push_str("<li>");
+            page.push_str(&summary);
+            page.push_str("</li>");
+        }
+        page.push_str("</ul>");
+    }
+}
+
 pub fn write_full_html_document_with_header(path: &str, title: &str, content: &str, header: &str) -> io::Result<()> {
     let index_file = fs::File::create(path)?;
     let mut writer = BufWriter::new(index_file);
@@ -242,6 +367,13 @@ pub fn write_full_html_document(path: &str, title: &str, content: &str) -> io::R
     Ok(())
 }
 
+pub fn write_json<T: Serialize>(path: &str, data: &T) -> io::Result<()> {
+    let file = fs::File::create(path)?;
+    let writer = BufWriter::new(file);
+    serde_json::to_writer(writer, data)?;
+    Ok(())
+}
+
 fn write_head(writer: &mut dyn Write, title: &str) -> io::Result<()> {
     writer.write_all(BEFORE_TITLE.as_bytes())?;
     writer.write_all(title.as_bytes())?;
@@ -252,3 +384,5 @@ fn write_head(writer: &mut dyn Write, title: &str) -> io::Result<()> {
 const BEFORE_TITLE: &str = include_str!("../../static/before_title.html");
 const BEFORE_BODY: &str = include_str!("../../static/before_body.html");
 const AFTER_BODY: &str = include_str!("../../static/after_body.html");
+/// `formatPrice` for all pages that add up prices in the browser
+pub(crate) const PRICE_SCRIPT: &str = include_str!("../../static/price.js");
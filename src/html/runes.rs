@@ -0,0 +1,189 @@
+use crate::{
+    data::{
+        equipment::Equipment,
+        runes::{can_have_runes, Rune, RuneKind, RunedItem},
+        HasName,
+    },
+    html::{write_full_html_document, HtmlPage, PRICE_SCRIPT},
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::io;
+
+const BUILDER_SCRIPT: &str = include_str!("../../static/builder.js");
+
+#[derive(Serialize, Debug)]
+struct BuilderData<'a> {
+    bases: Vec<BuilderBase<'a>>,
+    runes: Vec<BuilderRune<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct BuilderBase<'a> {
+    name: &'a str,
+    url: String,
+    level: i32,
+    price: u32,
+    /// e.g. `d8 Slashing`, without the number of dice which depends on the striking rune
+    die: Option<String>,
+    dice: i32,
+    ac_bonus: Option<i32>,
+    /// Indices of all runes that fit this item
+    runes: Vec<usize>,
+    upgrades: Vec<BuilderUpgrade>,
+}
+
+/// One step of the usual fundamental rune progression, e.g. `+1 Striking Longsword`
+#[derive(Serialize, Debug)]
+struct BuilderUpgrade {
+    name: String,
+    level: i32,
+    price: u32,
+    damage: Option<String>,
+    runes: Vec<usize>,
+}
+
+#[derive(Serialize, Debug)]
+struct BuilderRune<'a> {
+    name: &'a str,
+    url: String,
+    level: i32,
+    price: u32,
+    #[serde(flatten)]
+    kind: RuneKind,
+    /// Number of property runes this rune allows
+    property_slots: i32,
+    /// Indices of all runes that can’t be etched onto the same item as this one, including itself
+    conflicts: Vec<usize>,
+    extra_damage: Option<&'a str>,
+}
+
+/// The builder only gets the rules data computed here, the page itself just adds it up.
+pub fn render_rune_builder(target: &str, elements: &[(Equipment, HtmlPage)]) -> io::Result<()> {
+    let data = builder_data(elements.iter().map(|(e, _)| e));
+    let json = serde_json::to_string(&data)?.replace("</", "<\\/");
+    let mut page = String::with_capacity(json.len() + 5_000);
+    page.push_str("<h1>Rune Builder</h1><hr/>");
+    page.push_str("<p>Pick a weapon or armor and the runes etched onto it. Each point of potency allows one property rune.</p>");
+    page.push_str(r#"<div id="builder"></div><div id="builder-result"></div>"#);
+    page.push_str(r#"<script id="builder-data" type="application/json">"#);
+    page.push_str(&json);
+    page.push_str("</script><script>");
+    page.push_str(PRICE_SCRIPT);
+    page.push_str(BUILDER_SCRIPT);
+    page.push_str("</script>");
+    write_full_html_document(&format!("{}/builder", target), "Rune Builder", &page)
+}
+
+fn builder_data<'a>(items: impl Iterator<Item = &'a Equipment> + Clone) -> BuilderData<'a> {
+    let runes = items.clone().filter_map(Rune::from_item).collect_vec();
+    let bases = items
+        .filter(|i| can_have_runes(i))
+        .map(|base| BuilderBase {
+            name: &base.name,
+            url: base.url_name(),
+            level: base.level,
+            price: base.price.as_copper(),
+            die: base.damage.as_ref().map(|d| format!("{} {}", d.die, d.damage_type.as_ref())),
+            dice: base.damage.as_ref().map(|d| d.number_of_dice).unwrap_or_default(),
+            ac_bonus: base.armor.map(|a| a.ac_bonus),
+            runes: runes.iter().positions(|r| r.fits(base)).collect(),
+            upgrades: upgrades(base, &runes),
+        })
+        .collect();
+    let runes = runes
+        .iter()
+        .map(|r| BuilderRune {
+            name: &r.item.name,
+            url: r.item.url_name(),
+            level: r.item.level,
+            price: r.item.price.as_copper(),
+            kind: r.kind,
+            property_slots: r.property_slots(),
+            conflicts: runes.iter().positions(|other| r.conflicts_with(other)).collect(),
+            extra_damage: r.extra_damage(),
+        })
+        .collect();
+    BuilderData { bases, runes }
+}
+
+/// Potency and striking or resilient grades as they are usually upgraded during a campaign
+const PROGRESSION: [(i32, i32); 6] = [(1, 0), (1, 1), (2, 1), (2, 2), (3, 2), (3, 3)];
+
+fn upgrades(base: &Equipment, runes: &[Rune]) -> Vec<BuilderUpgrade> {
+    let find = |wanted: &dyn Fn(RuneKind) -> bool| runes.iter().position(|r| wanted(r.kind) && r.fits(base));
+    PROGRESSION
+        .iter()
+        .map_while(|&(potency, grade)| {
+            let potency = find(&|k| matches!(k, RuneKind::WeaponPotency(p) | RuneKind::ArmorPotency(p) if p == potency))?;
+            let grade = match grade {
+                0 => None,
+                _ => Some(find(
+                    &|k| matches!(k, RuneKind::Striking(g) | RuneKind::Resilient(g) if g == grade),
+                )?),
+            };
+            let indices = std::iter::once(potency).chain(grade).collect_vec();
+            let item = RunedItem::new(base, indices.iter().map(|&i| runes[i]).collect()).ok()?;
+            Some(BuilderUpgrade {
+                name: item.name(),
+                level: item.level(),
+                price: item.price().as_copper(),
+                damage: item.damage().map(|d| d.to_string()),
+                runes: indices,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::equipment;
+    use serde_json::json;
+
+    #[test]
+    fn should_only_offer_fitting_runes() {
+        let usage = |usage: &str| json!({ "usage": { "value": usage } });
+        let items = [
+            equipment(
+                "Longbow",
+                "weapon",
+                0,
+                0,
+                "common",
+                &[],
+                json!({ "range": 100, "damage": { "damageType": "piercing", "dice": 1, "die": "d8" } }),
+            ),
+            equipment("Vorpal", "equipment", 0, 0, "common", &[], usage("etched-onto-melee-weapon")),
+            equipment("Striking", "equipment", 0, 0, "common", &[], usage("etched-onto-a-weapon")),
+        ];
+        let data = builder_data(items.iter());
+        assert_eq!(data.bases.len(), 1);
+        assert_eq!(data.bases[0].runes, vec![1]);
+        assert_eq!(data.bases[0].die.as_deref(), Some("d8 Piercing"));
+        let json = serde_json::to_value(&data.runes[1]).unwrap();
+        assert_eq!(json["kind"], "striking");
+        assert_eq!(json["value"], 1);
+    }
+
+    #[test]
+    fn should_list_conflicting_property_runes() {
+        let rune = |name: &str| {
+            equipment(
+                name,
+                "equipment",
+                0,
+                0,
+                "common",
+                &[],
+                json!({ "usage": { "value": "etched-onto-a-weapon" } }),
+            )
+        };
+        let items = [rune("Flaming"), rune("Flaming (Greater)"), rune("Weapon Potency (+2)")];
+        let data = builder_data(items.iter());
+        assert_eq!(data.runes[0].conflicts, vec![0, 1]);
+        assert_eq!(data.runes[1].conflicts, vec![0, 1]);
+        assert!(data.runes[2].conflicts.is_empty());
+        assert_eq!(data.runes.iter().map(|r| r.property_slots).collect_vec(), vec![0, 0, 2]);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{
+    data::{actions::Action, backgrounds::Background, creature::Npc, feats::Feat, skills::Skill, HasLevel, HasName, ObjectName},
+    html::{
+        feats::{is_skill_feat_for, render_feats_by_level},
+        inline_rarity_if_not_common, write_full_html_document, HtmlPage,
+    },
+};
+use itertools::Itertools;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    fs, io,
+};
+use strum::IntoEnumIterator;
+
+/// Everything the skill pages collect from the other categories.
+pub struct SkillSources<'a> {
+    pub actions: &'a [(Action, HtmlPage)],
+    pub feats: &'a [(Feat, HtmlPage)],
+    pub backgrounds: &'a [(Background, HtmlPage)],
+    pub npcs: &'a [(Npc, HtmlPage)],
+}
+
+fn skills() -> impl Iterator<Item = Skill> {
+    Skill::iter().filter(|s| !matches!(s, Skill::Lore(_)))
+}
+
+fn skill_url(skill: &Skill) -> String {
+    skill.as_ref().to_lowercase()
+}
+
+/// Lores are written inconsistently as “Warfare” or “Warfare Lore”.
+fn lore_topic(lore: &str) -> &str {
+    lore.trim_end_matches(" Lore")
+}
+
+/// Renders one hub page per skill and an index of all lores.
+pub fn render_skills(target: &str, sources: &SkillSources) -> io::Result<()> {
+    fs::create_dir_all(target)?;
+    for skill in skills() {
+        write_full_html_document(
+            &format!("{}/{}", target, skill_url(&skill)),
+            skill.as_ref(),
+            &render_skill(&skill, sources),
+        )?;
+    }
+    write_full_html_document(&format!("{}/lore", target), "Lore", &render_lore_index(sources))?;
+    let mut index = String::with_capacity(2000);
+    add_skill_header(&mut index);
+    index.push_str("<h1>Skills</h1><hr/><div id=\"gridlist\">");
+    for skill in skills() {
+        write!(index, "<span><a href=\"{}\">{}</a></span>", skill_url(&skill), skill.as_ref());
+    }
+    index.push_str("<span><a href=\"lore\">Lore</a></span></div>");
+    write_full_html_document(&format!("{}/index.html", target), "Skills", &index)
+}
+
+fn add_skill_header(page: &mut String) {
+    page.push_str(r#"<div class="header">"#);
+    for skill in skills() {
+        write!(
+            page,
+            r#"<span><a href="/skill/{}"><div>{}</div></a></span>"#,
+            skill_url(&skill),
+            skill.as_ref()
+        );
+    }
+    page.push_str(r#"<span><a href="/skill/lore"><div>Lore</div></a></span>"#);
+    page.push_str("</div>");
+}
+
+/// Actions don’t say which skill they use, so we look for the check in the description.
+fn skill_actions<'a>(skill: &Skill, actions: &'a [(Action, HtmlPage)]) -> (Vec<&'a Action>, Vec<&'a Action>) {
+    let check = format!("{} check", skill.as_ref().to_lowercase());
+    let trained = format!("trained in {}", skill.as_ref().to_lowercase());
+    actions
+        .iter()
+        .map(|(a, _)| a)
+        .filter(|a| a.description.to_lowercase().contains(&check))
+        .partition(|a| {
+            let description = a.description.to_lowercase();
+            !description.contains(&trained) && !description.contains("must be trained")
+        })
+}
+
+fn render_action_list(page: &mut String, title: &str, actions: &[&Action]) {
+    if !actions.is_empty() {
+        write!(page, "<h2>{}</h2><ul>", title);
+        for action in actions {
+            write!(
+                page,
+                "<li><a href=\"/action/{}\">{}</a> {}</li>",
+                action.url_name(),
+                action.name,
+                action.action_type.img(&action.number_of_actions)
+            );
+        }
+        page.push_str("</ul>");
+    }
+}
+
+/// The creature with the highest modifier in the skill for each level.
+fn highest_modifiers(npcs: &[(Npc, HtmlPage)], is_skill: impl Fn(&Skill) -> bool) -> BTreeMap<i32, (i32, &str)> {
+    let mut highest = BTreeMap::new();
+    for (npc, _) in npcs {
+        if let Npc::Creature(creature) = npc {
+            for &(_, modifier) in creature.skills.iter().filter(|(s, _)| is_skill(s)) {
+                let entry = highest.entry(npc.level()).or_insert((modifier, npc.name()));
+                if modifier > entry.0 {
+                    *entry = (modifier, npc.name());
+                }
+            }
+        }
+    }
+    highest
+}
+
+fn render_skill(skill: &Skill, sources: &SkillSources) -> String {
+    let mut page = String::with_capacity(20_000);
+    add_skill_header(&mut page);
+    write!(page, "<h1><a href=\"/skill/{}\">{}</a></h1><hr/>", skill_url(skill), skill.as_ref());
+
+    let (untrained, trained) = skill_actions(skill, sources.actions);
+    render_action_list(&mut page, "Untrained Actions", &untrained);
+    render_action_list(&mut page, "Trained Actions", &trained);
+
+    page.push_str("<h2>Skill Feats</h2>");
+    render_feats_by_level(
+        &mut page,
+        sources
+            .feats
+            .iter()
+            .map(|(f, _)| f)
+            .filter(|f| is_skill_feat_for(f, skill.as_ref())),
+    );
+
+    let backgrounds = sources.backgrounds.iter().filter(|(b, _)| b.skills.contains(skill)).collect_vec();
+    if !backgrounds.is_empty() {
+        page.push_str("<h2>Backgrounds</h2><div id=\"gridlist\">");
+        for (background, _) in backgrounds {
+            write!(
+                page,
+                "<span><a href=\"/background/{}\">{}</a>{}</span>",
+                background.url_name(),
+                background.name,
+                inline_rarity_if_not_common(&background.traits.rarity)
+            );
+        }
+        page.push_str("</div>");
+    }
+
+    let highest = highest_modifiers(sources.npcs, |s| s == skill);
+    if !highest.is_empty() {
+        page.push_str("<h2>Highest Creature Modifiers</h2>");
+        page.push_str("<table class=\"overview\"><thead><tr><td>Level</td><td>Modifier</td><td>Creature</td></tr></thead>");
+        for (level, (modifier, creature)) in highest {
+            write!(
+                page,
+                "<tr><td>{}</td><td>{:+}</td><td><a href=\"/creature/{}\">{}</a></td></tr>",
+                level,
+                modifier,
+                ObjectName(creature).url_name(),
+                creature
+            );
+        }
+        page.push_str("</table>");
+    }
+    page
+}
+
+fn render_lore_index(sources: &SkillSources) -> String {
+    let mut backgrounds: BTreeMap<&str, Vec<&Background>> = BTreeMap::new();
+    for (background, _) in sources.backgrounds.iter().filter(|(b, _)| b.lore != "none") {
+        backgrounds.entry(lore_topic(&background.lore)).or_default().push(background);
+    }
+    let mut creatures: HashMap<&str, usize> = HashMap::new();
+    for (npc, _) in sources.npcs {
+        if let Npc::Creature(creature) = npc {
+            for (skill, _) in &creature.skills {
+                if let Skill::Lore(lore) = skill {
+                    *creatures.entry(lore_topic(lore)).or_default() += 1;
+                }
+            }
+        }
+    }
+    let mut page = String::with_capacity(50_000);
+    add_skill_header(&mut page);
+    page.push_str("<h1>Lore</h1><hr/>");
+    page.push_str("<table class=\"overview\"><thead><tr><td>Lore</td><td>Backgrounds</td><td>Creatures</td></tr></thead>");
+    for topic in backgrounds.keys().chain(creatures.keys()).sorted().dedup() {
+        let background_links = backgrounds
+            .get(topic)
+            .map(|bs| {
+                bs.iter()
+                    .map(|b| format!("<a href=\"/background/{}\">{}</a>", b.url_name(), b.name))
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        write!(
+            page,
+            "<tr><td>{} Lore</td><td>{}</td><td>{}</td></tr>",
+            topic,
+            background_links,
+            creatures.get(topic).copied().unwrap_or_default()
+        );
+    }
+    page.push_str("</table>");
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{
+        action_type::ActionType,
+        actions::ActionBlock,
+        outcomes::Outcomes,
+        traits::{Rarity, Traits},
+    };
+
+    fn action(name: &str, description: &str) -> (Action, HtmlPage) {
+        let action = Action {
+            name: name.to_string(),
+            description: description.to_string(),
+            block: ActionBlock::default(),
+            outcomes: Outcomes::default(),
+            action_type: ActionType::Action,
+            number_of_actions: Some(1),
+            traits: Traits {
+                misc: vec![],
+                rarity: Rarity::Common,
+                size: None,
+                alignment: None,
+            },
+        };
+        (action, HtmlPage::default())
+    }
+
+    #[test]
+    fn should_split_skill_actions_by_training() {
+        let actions = [
+            action("Climb", "<p>Attempt an Athletics check to move up.</p>"),
+            action("Force Open", "<p>Attempt an Athletics check.</p>"),
+            action(
+                "Disable a Device",
+                "<p>You must be trained in Thievery. Attempt a Thievery check.</p>",
+            ),
+            action("Pick a Lock", "<p>Requirements You must be trained. Attempt a Thievery check.</p>"),
+        ];
+        let names = |actions: Vec<&Action>| actions.iter().map(|a| a.name.clone()).collect_vec();
+        let (untrained, trained) = skill_actions(&Skill::Athletics, &actions);
+        assert_eq!(names(untrained), vec!["Climb", "Force Open"]);
+        assert!(trained.is_empty());
+        let (untrained, trained) = skill_actions(&Skill::Thievery, &actions);
+        assert!(untrained.is_empty());
+        assert_eq!(names(trained), vec!["Disable a Device", "Pick a Lock"]);
+    }
+
+    #[test]
+    fn should_normalize_lore_topics() {
+        assert_eq!(lore_topic("Warfare Lore"), "Warfare");
+        assert_eq!(lore_topic("Warfare"), "Warfare");
+    }
+}
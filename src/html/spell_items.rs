@@ -0,0 +1,150 @@
+use crate::{
+    data::{
+        spell_items::{rank_as_string, SpellItemKind, StaffSpells},
+        spells::Spell,
+        traits::Traits,
+        HasName,
+    },
+    html::{render_traits, write_full_html_document, HtmlPage},
+    URL_REPLACEMENTS,
+};
+use itertools::Itertools;
+use std::{borrow::Cow, fmt::Write, io};
+use strum::IntoEnumIterator;
+
+/// One scroll and one wand page per spell, with the item level and price for every rank it can be heightened to.
+pub(crate) fn render_spell_items(target: &str, spells: &[(Spell, HtmlPage)]) -> io::Result<Vec<HtmlPage>> {
+    let mut pages = Vec::new();
+    for (spell, _) in spells {
+        for kind in SpellItemKind::iter() {
+            if let Some(content) = render_spell_item(kind, spell) {
+                let name = kind.item_name(spell);
+                write_full_html_document(&format!("{}/{}", target, kind.url_name(spell)), &name, &content)?;
+                pages.push(HtmlPage {
+                    id: format!("{}-{}", kind.category(), URL_REPLACEMENTS.replace_all(&name, "")),
+                    category: kind.category().to_string(),
+                    price_copper: kind.ranks(spell).first().map(|(_, _, price)| price.as_copper()),
+                    name,
+                    content,
+                    ..HtmlPage::default()
+                });
+            }
+        }
+    }
+    Ok(pages)
+}
+
+fn render_spell_item(kind: SpellItemKind, spell: &Spell) -> Option<String> {
+    let ranks = kind.ranks(spell);
+    let &(rank, level, price) = ranks.first()?;
+    let mut page = String::with_capacity(2_000);
+    write!(
+        page,
+        r#"<h1><a href="/item/{}">{}</a><span class="type">{} {}</span></h1><hr/>"#,
+        kind.url_name(spell),
+        kind.item_name(spell),
+        kind.category(),
+        level,
+    );
+    let traits = match kind {
+        SpellItemKind::Scroll => ["consumable", "magical", "scroll"].as_slice(),
+        SpellItemKind::Wand => ["magical", "wand"].as_slice(),
+    };
+    render_traits(
+        &mut page,
+        &Traits {
+            misc: traits.iter().map(|t| t.to_string()).collect(),
+            rarity: spell.traits.rarity,
+            alignment: None,
+            size: None,
+        },
+    );
+    write!(
+        page,
+        r#"<b>Spell</b> <a href="/spell/{}">{}</a> ({} rank)<br/><b>Price</b> {}<br/><b>Usage</b> held in 1 hand; <b>Bulk</b> L<hr/>"#,
+        spell.url_name(),
+        spell.name,
+        rank_as_string(rank),
+        price,
+    );
+    page.push_str(match kind {
+        SpellItemKind::Scroll => "<p>A scroll contains a single spell that you can cast without having to expend a spell slot. A scroll can be Crafted to contain nearly any spell, so the types of scrolls available are limited only by the number of spells in the game. The exceptions are cantrips, focus spells, and rituals, none of which can be put on scrolls.</p>",
+        SpellItemKind::Wand => "<p>A magic wand contains a spell that can be cast once per day. You can Overcharge the wand to cast the spell again, but this risks breaking it. A wand can be Crafted for any spell that isn’t a cantrip, focus spell or ritual, up to 9th rank.</p>",
+    });
+    page.push_str("<table class=\"overview\"><thead><tr><td>Rank</td><td>Item</td><td>Level</td><td>Price</td></tr></thead>");
+    for (rank, level, price) in ranks {
+        write!(
+            page,
+            "<tr><td>{}</td><td>{} ({} rank)</td><td>{}</td><td>{}</td></tr>",
+            rank_as_string(rank),
+            kind.item_name(spell),
+            rank_as_string(rank),
+            level,
+            price,
+        );
+    }
+    page.push_str("</table>");
+    Some(page)
+}
+
+/// Links to the scroll and wand pages of a spell, e.g. `scroll (level 5, 30 gp)`
+pub(crate) fn render_spell_item_links(page: &mut String, spell: &Spell) {
+    let links = SpellItemKind::iter()
+        .filter_map(|kind| {
+            let &(_, level, price) = kind.ranks(spell).first()?;
+            Some(format!(
+                r#"<a href="/item/{}">{}</a> (level {}, {})"#,
+                kind.url_name(spell),
+                kind.as_ref().to_lowercase(),
+                level,
+                price.to_string().trim_end()
+            ))
+        })
+        .collect_vec();
+    if !links.is_empty() {
+        write!(page, "<b>Items</b> {}<br/>", links.join(", "));
+    }
+}
+
+/// Replaces the spell list in a staff description with a table of linked spells by rank.
+pub(crate) fn render_staff_spells(description: &str) -> Cow<'_, str> {
+    let spells = match StaffSpells::parse(description) {
+        Some(spells) => spells,
+        None => return Cow::Borrowed(description),
+    };
+    let mut page = String::with_capacity(description.len() + 500);
+    page.push_str(&description[..spells.list.start]);
+    page.push_str("<table class=\"overview\"><thead><tr><td>Rank</td><td>Spells</td></tr></thead>");
+    for (rank, entries) in &spells.ranks {
+        write!(
+            page,
+            "<tr><td>{}</td><td>{}</td></tr>",
+            if *rank == 0 {
+                Cow::Borrowed("Cantrip")
+            } else {
+                Cow::Owned(rank_as_string(*rank))
+            },
+            entries.iter().map(|s| format!(r#"<a href="{}">{}</a>"#, s.url, s.name)).join(", "),
+        );
+    }
+    page.push_str("</table>");
+    page.push_str(&description[spells.list.end..]);
+    Cow::Owned(page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_render_staff_spells_as_table() {
+        let description = "<p>An ash staff.</p><ul><li><strong>Cantrip</strong> <em>light</em></li><li><strong>2nd</strong> <a href=\"/spell/invisibility\">invisibility</a></li></ul><p>Craft Requirements</p>";
+        assert_eq!(
+            render_staff_spells(description),
+            "<p>An ash staff.</p><table class=\"overview\"><thead><tr><td>Rank</td><td>Spells</td></tr></thead>\
+            <tr><td>Cantrip</td><td><a href=\"/spell/light\">light</a></td></tr>\
+            <tr><td>2nd</td><td><a href=\"/spell/invisibility\">invisibility</a></td></tr></table><p>Craft Requirements</p>"
+        );
+        assert_eq!(render_staff_spells("<p>A stick.</p>"), "<p>A stick.</p>");
+    }
+}
@@ -1,22 +1,27 @@
 use super::render_traits;
 use crate::{
     data::{
+        grants::Grants,
+        outcomes::Outcomes,
         spells::{Area, Spell, SpellCategory, SpellTradition},
         traits::Translations,
         HasLevel, HasName,
     },
-    html::{render_trait_legend, render_traits_inline, write_full_html_document, HtmlPage, Template},
+    html::{
+        render_grants, render_outcomes, render_trait_legend, render_traits_inline, spell_items::render_spell_item_links,
+        write_full_html_document, HtmlPage, Template,
+    },
     HTML_FORMATTING_TAGS,
 };
 use itertools::Itertools;
 use std::{borrow::Cow, fmt::Write, io};
 
-impl Template<&Translations> for Spell {
-    fn render(&self, trait_descriptions: &Translations) -> std::borrow::Cow<'_, str> {
-        Cow::Owned(render_spell(self, trait_descriptions))
+impl Template<(&Translations, &Grants)> for Spell {
+    fn render(&self, (trait_descriptions, grants): (&Translations, &Grants)) -> std::borrow::Cow<'_, str> {
+        Cow::Owned(render_spell(self, trait_descriptions, grants))
     }
 
-    fn render_subindices(target: &str, elements: &[(Self, HtmlPage)]) -> io::Result<()> {
+    fn render_subindices(target: &str, elements: &[(Self, HtmlPage)], _: (&Translations, &Grants)) -> io::Result<()> {
         write_full_html_document(
             &format!("{}/{}", target, "arcane"),
             "Arcane Spells",
@@ -37,24 +42,20 @@ impl Template<&Translations> for Spell {
             "Primal Spells",
             &render_tradition(elements, SpellTradition::Primal),
         )?;
-
-        for t in elements.iter().flat_map(|(s, _)| &s.traits.misc).unique() {
-            let mut page = String::with_capacity(250_000);
-            page = add_spell_header(page);
-            write!(page, "<h1>{} Spells</h1><hr><br/>", t);
-            add_spell_list(&mut page, elements, |(s, _)| s.traits.misc.contains(t));
-            write_full_html_document(&format!("{}/trait_{}", target, t.to_lowercase()), &format!("{} Spells", t), &page)?;
-        }
         Ok(())
     }
 
-    fn render_index(elements: &[(Self, HtmlPage)]) -> String {
+    fn render_index(elements: &[(Self, HtmlPage)], _: (&Translations, &Grants)) -> String {
         render_full_spell_list(elements)
     }
 
     fn category(&self) -> Cow<'_, str> {
         Cow::Borrowed(if self.is_cantrip() { SpellCategory::Cantrip } else { self.category }.into())
     }
+
+    fn outcomes(&self) -> Option<&Outcomes> {
+        Some(&self.outcomes)
+    }
 }
 
 // TODO: dedup this with the strings in [parser]
@@ -79,7 +80,7 @@ fn get_action_img(val: &str) -> Option<&'static str> {
     }
 }
 
-fn render_spell(spell: &Spell, trait_descriptions: &Translations) -> String {
+fn render_spell(spell: &Spell, trait_descriptions: &Translations, grants: &Grants) -> String {
     let mut page = String::with_capacity(4000);
     write!(
         page,
@@ -138,8 +139,10 @@ fn render_spell(spell: &Spell, trait_descriptions: &Translations) -> String {
         page.push_str(save.into());
         page.push_str("<br/>");
     }
+    render_spell_item_links(&mut page, spell);
     page.push_str("<hr/>");
-    page.push_str(&spell.description);
+    page.push_str(&render_outcomes(&spell.description, &spell.outcomes));
+    render_grants(&mut page, grants, "spell", spell);
     page.push_str("<hr/>");
     render_trait_legend(&mut page, &spell.traits, trait_descriptions);
     page
@@ -245,7 +248,7 @@ mod tests {
             serde_json::from_str(&read_test_file("spells.db/resurrect.json")).expect("Deserialization of resurrect failed");
         let spells = vec![heal, resurrect]
             .into_iter()
-            .map(|s| attach_html(s, &TRANSLATIONS))
+            .map(|s| attach_html(s, (&*TRANSLATIONS, &Grants::default())))
             .collect_vec();
         assert_eq_ignore_linebreaks(&render_full_spell_list(&spells), include_str!("../../tests/html/spell_list.html"));
     }
@@ -253,12 +256,18 @@ mod tests {
     #[test]
     fn test_spell_template() {
         let heal: Spell = serde_json::from_str(&read_test_file("spells.db/heal.json")).expect("Deserialization failed");
-        assert_eq_ignore_linebreaks(&render_spell(&heal, &TRANSLATIONS), include_str!("../../tests/html/heal.html"));
+        assert_eq_ignore_linebreaks(
+            &render_spell(&heal, &TRANSLATIONS, &Grants::default()),
+            include_str!("../../tests/html/heal.html"),
+        );
     }
 
     #[test]
     fn test_spell_template2() {
         let res: Spell = serde_json::from_str(&read_test_file("spells.db/resurrect.json")).expect("Deserialization failed");
-        assert_eq_ignore_linebreaks(&res.render(&TRANSLATIONS), include_str!("../../tests/html/resurrect.html"));
+        assert_eq_ignore_linebreaks(
+            &res.render((&TRANSLATIONS, &Grants::default())),
+            include_str!("../../tests/html/resurrect.html"),
+        );
     }
 }
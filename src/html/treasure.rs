@@ -0,0 +1,110 @@
+use crate::{
+    data::{
+        equipment::{Equipment, Price},
+        traits::Rarity,
+        treasure::{LootCandidates, TreasureRow, TREASURE_BY_LEVEL},
+        HasName,
+    },
+    html::{write_full_html_document, HtmlPage, PRICE_SCRIPT},
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Write, io};
+
+const LOOT_SCRIPT: &str = include_str!("../../static/loot.js");
+
+#[derive(Serialize, Debug)]
+struct LootData<'a> {
+    table: &'static [TreasureRow],
+    permanent_items: BTreeMap<i32, Vec<LootItem<'a>>>,
+    consumables: BTreeMap<i32, Vec<LootItem<'a>>>,
+}
+
+#[derive(Serialize, Debug)]
+struct LootItem<'a> {
+    name: &'a str,
+    url: String,
+    price: u32,
+    rarity: Rarity,
+}
+
+/// The page rolls with the same generator and candidate order as `LootCandidates::generate`,
+/// so a seed gives the same loot here and on the command line.
+pub fn render_loot_page(target: &str, elements: &[(Equipment, HtmlPage)]) -> io::Result<()> {
+    let candidates = LootCandidates::new(elements.iter().map(|(e, _)| e), true);
+    let data = LootData {
+        table: &TREASURE_BY_LEVEL,
+        permanent_items: loot_items(&candidates.permanent_items),
+        consumables: loot_items(&candidates.consumables),
+    };
+    let json = serde_json::to_string(&data)?.replace("</", "<\\/");
+    let mut page = String::with_capacity(json.len() + 10_000);
+    page.push_str("<h1>Treasure by Level</h1><hr/>");
+    page.push_str(
+        r#"<p><b>Party Level</b> <input id="loot-level" type="number" min="1" max="20" value="1"/>
+        <b>Party Size</b> <input id="loot-size" type="number" min="1" max="10" value="4"/>
+        <b>Seed</b> <input id="loot-seed" type="number" min="0"/>
+        <label><input id="loot-uncommon" type="checkbox"/> Include uncommon and rare items</label>
+        <button id="loot-roll">Roll</button></p><div id="loot-result"></div>"#,
+    );
+    render_treasure_table(&mut page);
+    page.push_str(r#"<script id="loot-data" type="application/json">"#);
+    page.push_str(&json);
+    page.push_str("</script><script>");
+    page.push_str(PRICE_SCRIPT);
+    page.push_str(LOOT_SCRIPT);
+    page.push_str("</script>");
+    write_full_html_document(&format!("{}/loot", target), "Treasure by Level", &page)
+}
+
+fn loot_items<'a>(candidates: &BTreeMap<i32, Vec<&'a Equipment>>) -> BTreeMap<i32, Vec<LootItem<'a>>> {
+    candidates
+        .iter()
+        .map(|(&level, items)| {
+            let items = items
+                .iter()
+                .map(|i| LootItem {
+                    name: &i.name,
+                    url: i.url_name(),
+                    price: i.price.as_copper(),
+                    rarity: i.traits.rarity,
+                })
+                .collect();
+            (level, items)
+        })
+        .collect()
+}
+
+fn render_treasure_table(page: &mut String) {
+    page.push_str("<h2>Party Treasure by Level</h2><table class=\"overview\">");
+    page.push_str(
+        "<thead><tr><td>Level</td><td>Permanent Items</td><td>Consumables</td><td>Currency</td><td>Per Additional PC</td></tr></thead>",
+    );
+    let slots = |slots: &[(i32, usize)]| slots.iter().map(|(level, count)| format!("{}× level {}", count, level)).join(", ");
+    for row in &TREASURE_BY_LEVEL {
+        write!(
+            page,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            row.level,
+            slots(row.permanent_items),
+            slots(row.consumables),
+            row.currency(4),
+            Price::from_copper(row.currency_per_additional_pc * 100),
+        );
+    }
+    page.push_str("</table>");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_render_treasure_table() {
+        let mut page = String::new();
+        render_treasure_table(&mut page);
+        assert!(page
+            .contains("<tr><td>1</td><td>2× level 2, 2× level 1</td><td>2× level 2, 3× level 1</td><td>40 gp </td><td>10 gp </td></tr>"));
+        assert!(page.contains("<tr><td>20</td><td>4× level 20</td><td>4× level 20</td><td>140,000 gp </td><td>35,000 gp </td></tr>"));
+    }
+}
@@ -2,7 +2,7 @@
 #![allow(unused_must_use)]
 #[macro_use]
 extern crate strum;
-use crate::data::creature::Npc;
+use crate::data::creature::{CreatureFamilies, Npc};
 use data::{
     actions::Action,
     ancestries::Ancestry,
@@ -12,14 +12,24 @@ use data::{
     classes::Class,
     conditions::Condition,
     deities::Deity,
-    equipment::Equipment,
+    equipment::{Equipment, ItemFamilies, ItemType},
     feats::Feat,
+    grants::Grants,
     heritages::Heritage,
+    prerequisites::PrerequisiteGraph,
     spells::Spell,
-    traits::{read_translations, render_traits, Translations},
+    traits::{read_translations, render_traits, TraitIndex, TraitKind, Translations},
+    treasure::{treasure_row, LootCandidates},
 };
 use futures::executor::block_on;
-use html::render;
+use html::{
+    actions::write_activities_json,
+    classes::render_progressions,
+    feats::{render_feat_trees, FeatLists},
+    read_compendium, render, render_loaded,
+    skills::{render_skills, SkillSources},
+    spell_items::render_spell_items,
+};
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use meilisearch_sdk::client::*;
@@ -28,6 +38,7 @@ use regex::Regex;
 use std::{
     fs, io,
     sync::atomic::{AtomicI32, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 mod data;
@@ -35,7 +46,10 @@ mod html;
 mod parser;
 
 lazy_static! {
-    static ref DATA_PATH: String = std::env::args().nth(1).unwrap_or_else(|| String::from("foundry"));
+    static ref DATA_PATH: String = std::env::args()
+        .nth(1)
+        .filter(|arg| arg != LOOT_COMMAND)
+        .unwrap_or_else(|| String::from("foundry"));
 
     static ref TRANSLATIONS: Translations = read_translations(
         &format!("{}/static/lang/en.json", get_data_path()),
@@ -50,13 +64,15 @@ lazy_static! {
 
 static FAILED_COMPENDIA: AtomicI32 = AtomicI32::new(0);
 
+const LOOT_COMMAND: &str = "loot";
+
 fn get_data_path() -> &'static str {
     &DATA_PATH
 }
 
 macro_rules! render_and_index {
-    ($type: ty, $source: expr, $target: literal, $additional: expr, $index: ident) => {
-        match render::<$type, _, _>(&$source, concat!("output/", $target), $additional) {
+    (@index $rendered: expr, $target: literal, $index: ident) => {
+        match $rendered {
             Ok(rendered) => {
                 if let Some(index) = &$index {
                     if let Err(e) = index
@@ -76,44 +92,227 @@ macro_rules! render_and_index {
             }
         }
     };
+    ($type: ty, loaded $elements: expr, $target: literal, $additional: expr, $index: ident) => {
+        render_and_index!(@index render_loaded::<$type, _>($elements, concat!("output/", $target), $additional), $target, $index)
+    };
+    ($type: ty, $source: expr, $target: literal, $additional: expr, $index: ident) => {
+        render_and_index!(@index render::<$type, _, _>(&$source, concat!("output/", $target), $additional), $target, $index)
+    };
+}
+
+macro_rules! load {
+    ($type: ty, $source: expr) => {
+        match read_compendium::<$type, _>(&$source) {
+            Ok(elements) => elements,
+            Err(e) => {
+                eprintln!("Error while reading {:?}: {}", $source, e);
+                FAILED_COMPENDIA.fetch_add(1, Ordering::SeqCst);
+                vec![]
+            }
+        }
+    };
 }
 
 fn main() {
+    let args = std::env::args().collect_vec();
+    if args.get(1).map(String::as_str) == Some(LOOT_COMMAND) {
+        std::process::exit(loot(&args[2..]));
+    }
     block_on(async move {
         let search_index = build_search_index().await;
 
-        match (render_traits("output/trait", &TRANSLATIONS), &search_index) {
-            (Ok(traits), Some(index)) => {
-                index.add_or_replace(&traits, None).await.unwrap();
-            }
-            (Ok(_), None) => println!("Successfully rendered descriptions"),
-            (Err(e), _) => eprintln!("Error while rendering descriptions: {}", e),
+        // Everything that can grant or be granted has to be loaded before rendering so both directions can be linked.
+        // Classes and ancestries are needed for the feat lists.
+        let feats = load!(Feat, ["feats.db"]);
+        let spells = load!(Spell, ["spells.db"]);
+        let actions = load!(Action, ["actions.db", "adventure-specific-actions.db"]);
+        let classfeatures = load!(ClassFeature, ["classfeatures.db"]);
+        let equipment = load!(Equipment, ["equipment.db"]);
+        let ancestryfeatures = load!(AncestryFeature, ["ancestryfeatures.db"]);
+        let heritages = load!(Heritage, ["heritages.db"]);
+        let classes = load!(Class, ["classes.db"]);
+        let ancestries = load!(Ancestry, ["ancestries.db"]);
+        let mut grants = Grants::default();
+        grants.add_targets("feat", &feats);
+        grants.add_targets("spell", &spells);
+        grants.add_targets("action", &actions);
+        grants.add_targets("classfeature", &classfeatures);
+        grants.add_targets("item", &equipment);
+        grants.add_targets("ancestryfeature", &ancestryfeatures);
+        grants.add_targets("heritage", &heritages);
+        grants.add_sources("feat", &feats);
+        grants.add_sources("classfeature", &classfeatures);
+        grants.add_sources("item", &equipment);
+        grants.add_sources("ancestryfeature", &ancestryfeatures);
+        grants.add_sources("heritage", &heritages);
+        let prerequisites = PrerequisiteGraph::new(&feats, &classfeatures);
+        let feat_lists = FeatLists::new(&classes, &ancestries, &heritages, &feats);
+        for feat in feat_lists.unmatched_feats(&feats) {
+            eprintln!(
+                "{:?} feat {} doesn’t match any class or ancestry (traits: {})",
+                feat.feat_type,
+                feat.name,
+                feat.traits.misc.join(", ")
+            );
         }
 
-        render_and_index!(Feat, ["feats.db"], "feat", &TRANSLATIONS, search_index);
-        render_and_index!(Spell, ["spells.db"], "spell", &TRANSLATIONS, search_index);
-        render_and_index!(Background, ["backgrounds.db"], "background", (), search_index);
-        render_and_index!(Action, ["actions.db", "adventure-specific-actions.db"], "action", (), search_index);
+        let feats = render_and_index!(
+            Feat,
+            loaded feats,
+            "feat",
+            (&TRANSLATIONS, &grants, &prerequisites, &feat_lists),
+            search_index
+        );
+        if let Err(e) = render_feat_trees("output/feat", &feats, &prerequisites, &feat_lists) {
+            eprintln!("Error while rendering feat trees: {}", e);
+            FAILED_COMPENDIA.fetch_add(1, Ordering::SeqCst);
+        }
+        let spells = render_and_index!(Spell, loaded spells, "spell", (&TRANSLATIONS, &grants), search_index);
+        let backgrounds = render_and_index!(Background, ["backgrounds.db"], "background", (), search_index);
+        let actions = render_and_index!(Action, loaded actions, "action", &grants, search_index);
         render_and_index!(Condition, ["conditions.db"], "condition", (), search_index);
         render_and_index!(Deity, ["deities.db"], "deity", (), search_index);
-        let classfeatures = render_and_index!(ClassFeature, ["classfeatures.db"], "classfeature", &TRANSLATIONS, search_index);
-        render_and_index!(Class, ["classes.db"], "class", &classfeatures, search_index);
-        render_and_index!(Equipment, ["equipment.db"], "item", &TRANSLATIONS, search_index);
-        render_and_index!(
+        let classfeatures = render_and_index!(
+            ClassFeature,
+            loaded classfeatures,
+            "classfeature",
+            (&TRANSLATIONS, &grants),
+            search_index
+        );
+        let classes = render_and_index!(Class, loaded classes, "class", (&classfeatures, &feats), search_index);
+        if let Err(e) = render_progressions("output/class", &classes, &classfeatures) {
+            eprintln!("Error while rendering class progressions: {}", e);
+            FAILED_COMPENDIA.fetch_add(1, Ordering::SeqCst);
+        }
+        let item_families = ItemFamilies::new(&equipment);
+        let equipment = render_and_index!(
+            Equipment,
+            loaded equipment,
+            "item",
+            (&TRANSLATIONS, &grants, &item_families),
+            search_index
+        );
+        match (render_spell_items("output/item", &spells), &search_index) {
+            (Ok(pages), Some(index)) => {
+                index.add_or_replace(&pages, Some("id")).await.unwrap();
+            }
+            (Ok(_), None) => println!("Successfully rendered scrolls and wands"),
+            (Err(e), _) => {
+                eprintln!("Error while rendering scrolls and wands: {}", e);
+                FAILED_COMPENDIA.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        let ancestryfeatures = render_and_index!(
             AncestryFeature,
-            ["ancestryfeatures.db"],
+            loaded ancestryfeatures,
             "ancestryfeature",
-            &TRANSLATIONS,
+            (&TRANSLATIONS, &grants),
             search_index
         );
-        render_and_index!(Ancestry, ["ancestries.db"], "ancestry", (), search_index);
-        render_and_index!(Heritage, ["heritages.db"], "heritage", (), search_index);
+        let heritages = render_and_index!(Heritage, loaded heritages, "heritage", &grants, search_index);
+        render_and_index!(Ancestry, loaded ancestries, "ancestry", (&heritages, &feats), search_index);
         let bestiaries = bestiary_folders().expect("Could not read bestiary folders");
-        render_and_index!(Npc, bestiaries, "creature", &TRANSLATIONS, search_index);
+        let npcs = load!(Npc, bestiaries);
+        let creature_families = CreatureFamilies::new(npcs.iter().filter_map(|n| match n {
+            Npc::Creature(c) => Some(c.as_ref()),
+            _ => None,
+        }));
+        let npcs = render_and_index!(Npc, loaded npcs, "creature", (&TRANSLATIONS, &creature_families), search_index);
+
+        let skill_sources = SkillSources {
+            actions: &actions,
+            feats: &feats,
+            backgrounds: &backgrounds,
+            npcs: &npcs,
+        };
+        if let Err(e) = render_skills("output/skill", &skill_sources) {
+            eprintln!("Error while rendering skills: {}", e);
+            FAILED_COMPENDIA.fetch_add(1, Ordering::SeqCst);
+        }
+        if let Err(e) = write_activities_json("output/action/activities.json", &actions, &feats, &spells, &npcs) {
+            eprintln!("Error while writing activities: {}", e);
+            FAILED_COMPENDIA.fetch_add(1, Ordering::SeqCst);
+        }
+
+        // Trait pages list everything with that trait, so they have to be rendered last.
+        let mut trait_index = TraitIndex::default();
+        trait_index.add_named(TraitKind::Class, feat_lists.classes());
+        trait_index.add_named(TraitKind::Ancestry, feat_lists.ancestries());
+        trait_index.add("feat", "Feats", &feats, |f| Some((TraitKind::Feat, &f.traits, Some(f.level))));
+        trait_index.add("spell", "Spells", &spells, |s| Some((TraitKind::Spell, &s.traits, Some(s.level))));
+        trait_index.add("item", "Items", &equipment, |i| {
+            let kind = match i.item_type {
+                ItemType::Weapon => TraitKind::Weapon,
+                ItemType::Armor | ItemType::Shield => TraitKind::Armor,
+                _ => TraitKind::Equipment,
+            };
+            Some((kind, &i.traits, Some(i.level)))
+        });
+        trait_index.add("action", "Actions", &actions, |a| Some((TraitKind::Action, &a.traits, None)));
+        trait_index.add("creature", "Creatures", &npcs, |n| match n {
+            Npc::Creature(c) => Some((TraitKind::Creature, &c.traits, Some(c.level))),
+            _ => None,
+        });
+        trait_index.add("ancestryfeature", "Ancestry Features", &ancestryfeatures, |f| {
+            Some((TraitKind::Ancestry, &f.traits, None))
+        });
+        trait_index.add("classfeature", "Class Features", &classfeatures, |f| {
+            Some((TraitKind::Class, &f.traits, Some(f.level)))
+        });
+        match (render_traits("output/trait", &TRANSLATIONS, &trait_index), &search_index) {
+            (Ok(traits), Some(index)) => {
+                index.add_or_replace(&traits, None).await.unwrap();
+            }
+            (Ok(_), None) => println!("Successfully rendered descriptions"),
+            (Err(e), _) => eprintln!("Error while rendering descriptions: {}", e),
+        }
     });
     std::process::exit(FAILED_COMPENDIA.load(Ordering::SeqCst)); // nonzero return if anything failed
 }
 
+/// `loot <party level> [party size] [seed] [--include-uncommon]`, reading the items from `./foundry`
+fn loot(args: &[String]) -> i32 {
+    let include_uncommon = args.iter().any(|a| a == "--include-uncommon");
+    let numbers = args
+        .iter()
+        .filter(|a| !a.starts_with("--"))
+        .map(|a| a.parse::<u32>().ok())
+        .collect_vec();
+    let random_seed = || SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).ok();
+    let (level, size, seed) = match numbers.as_slice() {
+        [Some(level)] => (*level as i32, Some(4), random_seed()),
+        [Some(level), size] => (*level as i32, *size, random_seed()),
+        [Some(level), size, seed] => (*level as i32, *size, *seed),
+        _ => (0, None, None),
+    };
+    let (size, seed) = match (size, seed) {
+        (Some(size), Some(seed)) if treasure_row(level).is_some() => (size, seed),
+        _ => {
+            eprintln!(
+                "Usage: {} <party level 1-20> [party size] [seed] [--include-uncommon]",
+                LOOT_COMMAND
+            );
+            return 1;
+        }
+    };
+    let equipment = load!(Equipment, ["equipment.db"]);
+    let candidates = LootCandidates::new(&equipment, include_uncommon);
+    let loot = match candidates.generate(level, size, seed) {
+        Some(loot) => loot,
+        None => return 1,
+    };
+    println!("Treasure for a party of {} at level {} (seed {})", size, level, seed);
+    for (title, items) in [("Permanent items", &loot.permanent_items), ("Consumables", &loot.consumables)] {
+        println!("{}:", title);
+        for item in items {
+            println!("  {} (level {}, {})", item.name, item.level, item.price.to_string().trim_end());
+        }
+    }
+    println!("Currency: {}", loot.currency.to_string().trim_end());
+    println!("Total value: {}", loot.total().to_string().trim_end());
+    FAILED_COMPENDIA.load(Ordering::SeqCst)
+}
+
 async fn build_search_index() -> Option<meilisearch_sdk::indexes::Index> {
     match std::env::var("MEILI_KEY") {
         Ok(key) => {
@@ -121,9 +320,18 @@ async fn build_search_index() -> Option<meilisearch_sdk::indexes::Index> {
             let search_index = client.index("all");
             // This sets the priority for searching
             search_index
-                .set_searchable_attributes(["name", "category", "content"])
+                .set_searchable_attributes([
+                    "name",
+                    "category",
+                    "content",
+                    "critical_success",
+                    "success",
+                    "failure",
+                    "critical_failure",
+                ])
                 .await
                 .unwrap();
+            search_index.set_filterable_attributes(["category", "price_copper"]).await.unwrap();
             search_index
                 .set_displayed_attributes(["name", "category", "content"])
                 .await
@@ -153,7 +361,14 @@ fn bestiary_folders() -> io::Result<Vec<String>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::{creature::Creature, traits::Translations};
+    use crate::data::{
+        action_type::ActionType,
+        actions::ActionBlock,
+        creature::Creature,
+        feat_type::FeatType,
+        outcomes::Outcomes,
+        traits::{Rarity, Traits, Translations},
+    };
     use pretty_assertions::assert_eq;
 
     pub fn read_test_file(path: &str) -> String {
@@ -173,6 +388,53 @@ mod tests {
         }
     }
 
+    /// A common passive feat without description or prerequisites
+    pub fn feat(name: &str, feat_type: FeatType, level: i32, traits: &[&str]) -> Feat {
+        Feat {
+            name: name.to_string(),
+            action_type: ActionType::Passive,
+            actions: None,
+            description: String::new(),
+            block: ActionBlock::default(),
+            outcomes: Outcomes::default(),
+            feat_type,
+            level,
+            prerequisites: vec![],
+            traits: Traits {
+                misc: traits.iter().map(|t| t.to_string()).collect(),
+                rarity: Rarity::Common,
+                size: None,
+                alignment: None,
+            },
+            source: String::new(),
+            rules: vec![],
+        }
+    }
+
+    /// An item without description. `extra` holds additional or overriding fields of the item's `system` object.
+    pub fn equipment(
+        name: &str,
+        item_type: &str,
+        level: i32,
+        gp: u32,
+        rarity: &str,
+        traits: &[&str],
+        extra: serde_json::Value,
+    ) -> Equipment {
+        let mut system = serde_json::json!({
+            "description": { "value": "" },
+            "source": { "value": "" },
+            "traits": { "value": traits, "rarity": rarity },
+            "level": { "value": level },
+            "price": { "value": { "gp": gp } },
+        });
+        system
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().expect("extra must be an object").clone());
+        serde_json::from_value(serde_json::json!({ "name": name, "type": item_type, "system": system })).expect("Deserialization failed")
+    }
+
     pub fn assert_eq_ignore_linebreaks(actual: &str, expected: &str) {
         assert_eq!(
             expected.lines().map(|l| l.trim()).collect::<String>(),
@@ -0,0 +1,98 @@
+(() => {
+    const data = JSON.parse(document.getElementById("builder-data").textContent);
+    const builder = document.getElementById("builder");
+    const result = document.getElementById("builder-result");
+
+    const select = (label, options) => {
+        const wrapper = document.createElement("p");
+        const element = document.createElement("select");
+        element.appendChild(new Option("—", ""));
+        for (const [value, text] of options) element.appendChild(new Option(text, value));
+        element.addEventListener("change", update);
+        wrapper.innerHTML = `<b>${label}</b> `;
+        wrapper.appendChild(element);
+        builder.appendChild(wrapper);
+        return element;
+    };
+
+    const runeOptions = (base, kinds) =>
+        base.runes.filter((i) => kinds.includes(data.runes[i].kind)).map((i) => [i, data.runes[i].name]);
+
+    const baseSelect = select("Base", data.bases.map((b, i) => [i, b.name]));
+    let runeSelects = [];
+
+    function rebuild() {
+        runeSelects.forEach((s) => s.parentElement.remove());
+        runeSelects = [];
+        const base = data.bases[baseSelect.value];
+        if (!base) return;
+        runeSelects.push(select("Potency", runeOptions(base, ["weapon_potency", "armor_potency"])));
+        runeSelects.push(select(base.die ? "Striking" : "Resilient", runeOptions(base, ["striking", "resilient"])));
+        for (let slot = 1; slot <= 3; slot++) {
+            runeSelects.push(select(`Property ${slot}`, runeOptions(base, ["weapon_property", "armor_property"])));
+        }
+    }
+
+    function update(event) {
+        if (event && event.target === baseSelect) rebuild();
+        const base = data.bases[baseSelect.value];
+        if (!base) {
+            result.innerHTML = "";
+            return;
+        }
+        const chosen = (selects) => selects.filter((s) => s.value !== "").map((s) => data.runes[s.value]);
+        const propertySelects = runeSelects.slice(2);
+        const slots = chosen(runeSelects.slice(0, 2)).reduce((sum, r) => sum + r.property_slots, 0);
+        propertySelects.forEach((s, i) => {
+            s.disabled = i >= slots;
+            if (s.disabled) s.value = "";
+        });
+        // runes that conflict with the ones in the other slots can’t be picked
+        for (const s of propertySelects) {
+            const taken = chosen(propertySelects.filter((other) => other !== s)).flatMap((r) => r.conflicts);
+            for (const option of s.options) option.disabled = option.value !== "" && taken.includes(Number(option.value));
+        }
+
+        const runes = chosen(runeSelects);
+        const value = (kinds) => runes.filter((r) => kinds.includes(r.kind)).map((r) => r.value)[0] || 0;
+        const potency = value(["weapon_potency", "armor_potency"]);
+        const grade = value(["striking", "resilient"]);
+        const properties = runes.filter((r) => r.kind.endsWith("_property"));
+
+        const lines = [
+            `<b>Level</b> ${Math.max(base.level, ...runes.map((r) => r.level))}`,
+            `<b>Price</b> ${formatPrice(runes.reduce((sum, r) => sum + r.price, base.price))}`,
+        ];
+        if (base.die) {
+            const extra = properties.filter((r) => r.extra_damage).map((r) => ` plus ${r.extra_damage}`).join("");
+            lines.push(`<b>Damage</b> ${base.dice + grade}${base.die}${extra}`);
+            if (potency) lines.push(`<b>Attack Bonus</b> +${potency} item bonus`);
+        } else {
+            lines.push(`<b>AC Bonus</b> +${base.ac_bonus + potency}`);
+            if (grade) lines.push(`<b>Saving Throws</b> +${grade} item bonus`);
+        }
+        const links = [base, ...runes].map((i) => `<a href="/item/${i.url}">${i.name}</a>`).join(", ");
+        lines.push(`<b>Items</b> ${links}`);
+        result.innerHTML = `<p>${lines.join("<br/>")}</p>`;
+        if (base.upgrades.length) result.appendChild(upgradeTable(base));
+    }
+
+    function upgradeTable(base) {
+        const table = document.createElement("table");
+        table.className = "overview";
+        table.innerHTML = "<thead><tr><td>Fundamental Runes</td><td>Level</td><td>Price</td><td>Damage</td></tr></thead>";
+        for (const upgrade of base.upgrades) {
+            const row = table.insertRow();
+            for (const text of [upgrade.name, upgrade.level, formatPrice(upgrade.price), upgrade.damage || ""]) {
+                row.insertCell().textContent = text;
+            }
+            row.style.cursor = "pointer";
+            row.addEventListener("click", () => {
+                runeSelects[0].value = upgrade.runes[0];
+                runeSelects[1].value = upgrade.runes[1] ?? "";
+                update();
+            });
+        }
+        return table;
+    }
+})();
@@ -0,0 +1,54 @@
+(() => {
+    const input = (id) => document.getElementById(id);
+    const rows = input("creature-index").querySelector("table").tBodies[0].rows;
+    const count = input("creature-count");
+
+    const fill = (select, values) => {
+        select.appendChild(new Option("Any", ""));
+        values.forEach((value, i) => select.appendChild(new Option(value, i)));
+    };
+    const selected = (id) => (input(id).value === "" ? null : Number(input(id).value));
+
+    fetch("/creature/creatures.json")
+        .then((response) => response.json())
+        .then((data) => {
+            fill(input("creature-size"), data.sizes);
+            fill(input("creature-rarity"), data.rarities);
+            fill(input("creature-alignment"), data.alignments);
+            fill(input("creature-source"), data.sources);
+            for (const trait of data.traits) input("creature-trait-list").appendChild(new Option(trait));
+
+            const update = () => {
+                const min = parseInt(input("creature-level-min").value);
+                const max = parseInt(input("creature-level-max").value);
+                const size = selected("creature-size");
+                const rarity = selected("creature-rarity");
+                const alignment = selected("creature-alignment");
+                const source = selected("creature-source");
+                // a trait that no creature has matches nothing
+                const traits = input("creature-traits")
+                    .value.split(",")
+                    .map((t) => t.trim().toLowerCase())
+                    .filter((t) => t)
+                    .map((t) => data.traits.indexOf(t));
+                let shown = 0;
+                data.creatures.forEach((c, i) => {
+                    const visible =
+                        !(c.level < min) &&
+                        !(c.level > max) &&
+                        (size === null || c.size === size) &&
+                        (rarity === null || c.rarity === rarity) &&
+                        (alignment === null || c.alignment === alignment) &&
+                        (source === null || c.source === source) &&
+                        traits.every((t) => c.traits.includes(t));
+                    rows[i].hidden = !visible;
+                    if (visible) shown++;
+                });
+                count.textContent = `${shown} of ${data.creatures.length} creatures`;
+            };
+            for (const id of ["creature-level-min", "creature-level-max", "creature-size", "creature-rarity", "creature-alignment", "creature-source", "creature-traits"]) {
+                input(id).addEventListener("input", update);
+            }
+            update();
+        });
+})();
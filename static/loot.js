@@ -0,0 +1,48 @@
+(() => {
+    const data = JSON.parse(document.getElementById("loot-data").textContent);
+    const input = (id) => document.getElementById(`loot-${id}`);
+    const seedInput = input("seed");
+
+    // Mulberry32 like the Rust generator, so the same seed rolls the same items
+    const rng = (seed) => {
+        let a = seed | 0;
+        return () => {
+            a = (a + 0x6d2b79f5) | 0;
+            let t = Math.imul(a ^ (a >>> 15), 1 | a);
+            t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+            return (t ^ (t >>> 14)) >>> 0;
+        };
+    };
+
+    function roll() {
+        const level = parseInt(input("level").value, 10);
+        const size = parseInt(input("size").value, 10) || 4;
+        const row = data.table.find((r) => r.level === level);
+        if (!row) return;
+        if (seedInput.value === "") seedInput.value = Math.floor(Math.random() * 2 ** 32);
+        const next = rng(parseInt(seedInput.value, 10));
+        const uncommon = input("uncommon").checked;
+        const pick = (slots, candidates) =>
+            slots.flatMap(([itemLevel, count]) => {
+                const items = (candidates[itemLevel] || []).filter((i) => uncommon || i.rarity === "common");
+                return items.length ? Array.from({ length: count }, () => items[next() % items.length]) : [];
+            });
+        const permanent = pick(row.permanent_items, data.permanent_items);
+        const consumables = pick(row.consumables, data.consumables);
+        const extra = row.currency_per_additional_pc * (size - 4);
+        const currency = Math.max(0, row.currency + extra) * 100;
+        const total = [...permanent, ...consumables].reduce((sum, i) => sum + i.price, currency);
+
+        const list = (items) =>
+            `<ul>${items.map((i) => `<li><a href="/item/${i.url}">${i.name}</a> (${formatPrice(i.price)})</li>`).join("")}</ul>`;
+        input("result").innerHTML =
+            `<h2>Permanent Items</h2>${list(permanent)}<h2>Consumables</h2>${list(consumables)}` +
+            `<p><b>Currency</b> ${formatPrice(currency)}<br/><b>Total Value</b> ${formatPrice(total)}</p>`;
+    }
+
+    input("roll").addEventListener("click", () => {
+        seedInput.value = "";
+        roll();
+    });
+    for (const id of ["level", "size", "seed", "uncommon"]) input(id).addEventListener("change", roll);
+})();
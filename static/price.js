@@ -0,0 +1,8 @@
+// Formats an amount of copper the way prices are written in the books, e.g. `1,200 gp 5 sp`
+const formatPrice = (copper) => {
+    const parts = [];
+    if (copper >= 100) parts.push(`${Math.floor(copper / 100).toLocaleString("en-US")} gp`);
+    if (copper % 100 >= 10) parts.push(`${Math.floor(copper / 10) % 10} sp`);
+    if (copper % 10) parts.push(`${copper % 10} cp`);
+    return parts.join(" ") || "—";
+};